@@ -126,7 +126,7 @@ async function moveMarket() {
         const minTokensOut = new BN(0); // Accept any amount for this test
         
         const tx = await program.methods
-            .buyOnCurve(team, usdcAmount, minTokensOut)
+            .buyOnCurve(team, usdcAmount, minTokensOut, null)
             .accounts({
                 buyer: wallet.publicKey,
                 market: marketPubkey,
@@ -0,0 +1,71 @@
+#!/usr/bin/env node
+
+// Local stand-in for the real result feed. Publishes (or updates) this
+// wallet's OracleResult for a game, then settles the game's market from it
+// once the result is final. Create the market with this wallet as its oracle.
+//
+//   node publish-result.js <gameId> <homeScore> <awayScore> [scheduled|inProgress|final|cancelled]
+
+import anchor from '@coral-xyz/anchor';
+import { Connection, PublicKey, Keypair } from '@solana/web3.js';
+import fs from 'fs';
+
+async function publishResult() {
+    const [gameId, homeScore, awayScore, status = 'final'] = process.argv.slice(2);
+    if (!gameId || homeScore === undefined || awayScore === undefined) {
+        console.error('Usage: node publish-result.js <gameId> <homeScore> <awayScore> [status]');
+        process.exit(1);
+    }
+
+    const connection = new Connection(process.env.RPC_URL || 'http://localhost:8899', 'confirmed');
+    const walletPath = process.env.WALLET_PATH || `${process.env.HOME}/.config/solana/id.json`;
+    const walletData = JSON.parse(fs.readFileSync(walletPath, 'utf8'));
+    const wallet = Keypair.fromSecretKey(new Uint8Array(walletData));
+
+    const provider = new anchor.AnchorProvider(connection, new anchor.Wallet(wallet), {});
+    anchor.setProvider(provider);
+
+    const idl = JSON.parse(fs.readFileSync('../target/idl/sportsxchange.json', 'utf8'));
+    const program = new anchor.Program(idl, provider);
+
+    const [oracleResult] = PublicKey.findProgramAddressSync(
+        [Buffer.from('oracle_result'), wallet.publicKey.toBuffer(), Buffer.from(gameId)],
+        program.programId
+    );
+    const [market] = PublicKey.findProgramAddressSync(
+        [Buffer.from('market'), Buffer.from(gameId)],
+        program.programId
+    );
+
+    const args = [Number(homeScore), Number(awayScore), { [status]: {} }];
+    const exists = await connection.getAccountInfo(oracleResult);
+    const tx = exists
+        ? await program.methods
+            .updateOracleResult(...args)
+            .accounts({ publisher: wallet.publicKey, oracleResult })
+            .rpc()
+        : await program.methods
+            .publishOracleResult(gameId, ...args)
+            .accounts({ publisher: wallet.publicKey })
+            .rpc();
+
+    console.log(`📡 ${gameId}: ${homeScore}-${awayScore} (${status})`);
+    console.log('   Feed:', oracleResult.toString());
+    console.log('   Tx:', tx);
+
+    if (status !== 'final' || !(await connection.getAccountInfo(market))) {
+        return;
+    }
+
+    try {
+        const resolveTx = await program.methods
+            .resolveFromOracle()
+            .accounts({ market, oracleResult })
+            .rpc();
+        console.log('✅ Market resolved:', resolveTx);
+    } catch (e) {
+        console.error('❌ Could not resolve market:', e.message);
+    }
+}
+
+publishResult().catch(console.error);
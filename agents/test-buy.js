@@ -31,7 +31,8 @@ async function testDirectCall() {
             .buyOnCurve(
                 0, // Team A
                 new BN(1000000), // 1 USDC
-                new BN(0) // No min tokens (for testing)
+                new BN(0), // No min tokens (for testing)
+                null
             )
             .accounts({
                 market: new PublicKey(marketData.marketPda),
@@ -0,0 +1,48 @@
+// Constant-product swaps (x * y = k), as used by the home/away AMM pools
+//
+// Selling dx of one reserve for the other pays
+//
+//   dy = y * dx / (x + dx)
+//
+// rounded down, so k never shrinks and the pool never pays out more than the
+// curve allows.
+
+use crate::{MathError, Result};
+
+// Tokens out of `reserve_out` for `amount_in` added to `reserve_in`
+pub fn amount_out(amount_in: u64, reserve_in: u64, reserve_out: u64) -> Result<u64> {
+    let numerator = (reserve_out as u128)
+        .checked_mul(amount_in as u128)
+        .ok_or(MathError::MathOverflow)?;
+
+    let denominator = (reserve_in as u128)
+        .checked_add(amount_in as u128)
+        .ok_or(MathError::MathOverflow)?;
+
+    let amount_out = numerator
+        .checked_div(denominator)
+        .ok_or(MathError::MathOverflow)?;
+
+    // Below reserve_out whenever the division is defined
+    Ok(amount_out as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn product_never_shrinks() {
+        for (reserve_in, reserve_out) in [(1_000_000u64, 1_000_000u64), (7, 1_000_000_000), (u64::MAX / 2, 3)] {
+            for amount_in in [1u64, 999, 1_000_000, u64::MAX / 2] {
+                let out = amount_out(amount_in, reserve_in, reserve_out).unwrap();
+                assert!(out < reserve_out || reserve_out == 0);
+                let before = reserve_in as u128 * reserve_out as u128;
+                let after = (reserve_in as u128 + amount_in as u128) * (reserve_out - out) as u128;
+                assert!(after >= before);
+            }
+        }
+        assert_eq!(amount_out(1_000, 1_000, 1_000).unwrap(), 500);
+        assert_eq!(amount_out(0, 0, 1_000), Err(MathError::MathOverflow));
+    }
+}
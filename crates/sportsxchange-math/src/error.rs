@@ -0,0 +1,30 @@
+use core::fmt;
+
+// Failures of the curve math. Each variant has the name and meaning of the
+// program's `ErrorCode` variant it converts into, so an error surfaces the
+// same way whether a trade fails on-chain or in a client-side preview.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MathError {
+    // A result does not fit its integer type
+    MathOverflow,
+    // More tokens sold than the supply holds
+    InsufficientSupply,
+}
+
+pub type Result<T> = core::result::Result<T, MathError>;
+
+impl MathError {
+    // The program's error message for the matching `ErrorCode`
+    pub fn message(&self) -> &'static str {
+        match self {
+            MathError::MathOverflow => "Math overflow",
+            MathError::InsufficientSupply => "Insufficient supply",
+        }
+    }
+}
+
+impl fmt::Display for MathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
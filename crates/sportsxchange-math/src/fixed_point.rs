@@ -0,0 +1,269 @@
+// Unsigned and signed Q64.64 fixed point (64 integer and 64 fractional
+// bits in a u128/i128) with deterministic log and exp, for curves that
+// need transcendental functions on-chain.
+//
+// Error bounds, per operation:
+//
+//   mul     rounds down, error < 2^-64
+//   log2    absolute error < 2^-56 (64 squarings, each truncating < 2^-64;
+//           the error of step i is weighted by 2^-i)
+//   exp2    relative error < 2^-57 (at most 64 table multiplications, each
+//           truncating < 2^-64 on a value in [1, 2)), plus < 2^-64 absolute
+//           from the final shift when the result is below one
+//   ln      absolute error < 2^-56, plus < |ln x| * 2^-63 from the LN_2 product
+//   exp     relative error < 2^-56 + |x| * 2^-63, plus < 2^-64 absolute
+
+pub const ONE: u128 = 1 << 64;
+const LOW_MASK: u128 = ONE - 1;
+
+// ln(2) and log2(e) in Q64.64, rounded down
+pub const LN_2: u128 = 0xb17217f7d1cf79ab;
+const LOG2_E: u128 = 0x171547652b82fe177;
+
+// 2^(2^-i) for i = 1..=64 in Q64.64, rounded down
+const EXP2_TABLE: [u128; 64] = [
+    0x00000000000000016a09e667f3bcc908,
+    0x0000000000000001306fe0a31b7152de,
+    0x0000000000000001172b83c7d517adcd,
+    0x00000000000000010b5586cf9890f629,
+    0x0000000000000001059b0d31585743ae,
+    0x000000000000000102c9a3e778060ee6,
+    0x00000000000000010163da9fb33356d8,
+    0x000000000000000100b1afa5abcbed61,
+    0x00000000000000010058c86da1c09ea1,
+    0x0000000000000001002c605e2e8cec50,
+    0x000000000000000100162f3904051fa1,
+    0x0000000000000001000b175effdc76ba,
+    0x000000000000000100058ba01fb9f96d,
+    0x00000000000000010002c5cc37da9491,
+    0x0000000000000001000162e525ee0547,
+    0x00000000000000010000b17255775c04,
+    0x0000000000000001000058b91b5bc9ae,
+    0x000000000000000100002c5c89d5ec6c,
+    0x00000000000000010000162e43f4f831,
+    0x000000000000000100000b1721bcfc99,
+    0x00000000000000010000058b90cf1e6d,
+    0x0000000000000001000002c5c863b73f,
+    0x000000000000000100000162e430e5a1,
+    0x0000000000000001000000b172183551,
+    0x000000000000000100000058b90c0b48,
+    0x00000000000000010000002c5c8601cc,
+    0x0000000000000001000000162e42fff0,
+    0x00000000000000010000000b17217fba,
+    0x0000000000000001000000058b90bfcd,
+    0x000000000000000100000002c5c85fe3,
+    0x00000000000000010000000162e42ff0,
+    0x000000000000000100000000b17217f8,
+    0x00000000000000010000000058b90bfb,
+    0x0000000000000001000000002c5c85fd,
+    0x000000000000000100000000162e42fe,
+    0x0000000000000001000000000b17217f,
+    0x000000000000000100000000058b90bf,
+    0x00000000000000010000000002c5c85f,
+    0x0000000000000001000000000162e42f,
+    0x00000000000000010000000000b17217,
+    0x0000000000000001000000000058b90b,
+    0x000000000000000100000000002c5c85,
+    0x00000000000000010000000000162e42,
+    0x000000000000000100000000000b1721,
+    0x00000000000000010000000000058b90,
+    0x0000000000000001000000000002c5c8,
+    0x000000000000000100000000000162e4,
+    0x0000000000000001000000000000b172,
+    0x000000000000000100000000000058b9,
+    0x00000000000000010000000000002c5c,
+    0x0000000000000001000000000000162e,
+    0x00000000000000010000000000000b17,
+    0x0000000000000001000000000000058b,
+    0x000000000000000100000000000002c5,
+    0x00000000000000010000000000000162,
+    0x000000000000000100000000000000b1,
+    0x00000000000000010000000000000058,
+    0x0000000000000001000000000000002c,
+    0x00000000000000010000000000000016,
+    0x0000000000000001000000000000000b,
+    0x00000000000000010000000000000005,
+    0x00000000000000010000000000000002,
+    0x00000000000000010000000000000001,
+    0x00000000000000010000000000000000,
+];
+
+// a * b in Q64.64, rounded down. None if the result needs more than 128 bits.
+pub fn mul(a: u128, b: u128) -> Option<u128> {
+    let (a1, a0) = (a >> 64, a & LOW_MASK);
+    let (b1, b0) = (b >> 64, b & LOW_MASK);
+
+    let high = a1 * b1;
+    if high >> 64 != 0 {
+        return None;
+    }
+
+    (high << 64)
+        .checked_add(a1 * b0)?
+        .checked_add(a0 * b1)?
+        .checked_add((a0 * b0) >> 64)
+}
+
+// log2(x) in signed Q64.64 for x > 0
+pub fn log2(x: u128) -> i128 {
+    debug_assert!(x > 0);
+
+    // Integer part: position of the top bit relative to the binary point
+    let msb = 127 - x.leading_zeros() as i128;
+    let integer = msb - 64;
+
+    // Normalize into [1, 2)
+    let mut y = if integer >= 0 {
+        x >> integer
+    } else {
+        x << -integer
+    };
+
+    // Fractional bits by repeated squaring
+    let mut fraction = 0u128;
+    for bit in (0..64).rev() {
+        // y < 2, so y^2 < 4 fits comfortably
+        y = mul(y, y).unwrap_or(u128::MAX);
+        if y >= 2 * ONE {
+            y >>= 1;
+            fraction |= 1 << bit;
+        }
+    }
+
+    (integer << 64) + fraction as i128
+}
+
+// 2^e for signed Q64.64 e. None if the result is 2^64 or more.
+pub fn exp2(e: i128) -> Option<u128> {
+    let integer = e >> 64;  // floor
+    let fraction = (e & LOW_MASK as i128) as u128;
+
+    if integer >= 64 {
+        return None;
+    }
+    if integer < -64 {
+        return Some(0);
+    }
+
+    let mut result = ONE;
+    for (i, factor) in EXP2_TABLE.iter().enumerate() {
+        if fraction & (1 << (63 - i)) != 0 {
+            result = mul(result, *factor)?;
+        }
+    }
+
+    Some(if integer >= 0 {
+        result.checked_shl(integer as u32).filter(|r| r >> integer == result)?
+    } else {
+        result >> -integer
+    })
+}
+
+// ln(x) in signed Q64.64 for x > 0
+pub fn ln(x: u128) -> i128 {
+    let log = log2(x);
+    // |log2 x| < 2^7, so the product always fits
+    let magnitude = mul(log.unsigned_abs(), LN_2).unwrap_or(u128::MAX) as i128;
+    if log < 0 {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
+// e^x for signed Q64.64 x. None if the result is 2^64 or more.
+pub fn exp(x: i128) -> Option<u128> {
+    match mul(x.unsigned_abs(), LOG2_E) {
+        Some(e) if x < 0 => Some(i128::try_from(e).map_or(0, |e| exp2(-e).unwrap_or(0))),
+        Some(e) => exp2(i128::try_from(e).ok()?),
+        // Far below the smallest representable value
+        None if x < 0 => Some(0),
+        None => None,
+    }
+}
+
+// Q64.64 to integer, rounded down or up
+pub fn to_integer(value: u128, round_up: bool) -> u128 {
+    if round_up {
+        value.div_ceil(ONE)
+    } else {
+        value >> 64
+    }
+}
+
+// value * numerator / denominator without overflowing the intermediate product
+pub fn mul_div(value: u128, numerator: u128, denominator: u128, round_up: bool) -> Option<u128> {
+    let whole = (value / denominator).checked_mul(numerator)?;
+    let remainder = (value % denominator) * numerator;
+    let part = if round_up {
+        remainder.div_ceil(denominator)
+    } else {
+        remainder / denominator
+    };
+    whole.checked_add(part)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{to_f64, Rng};
+
+    #[test]
+    fn log2_matches_f64() {
+        let mut rng = Rng(0x5eed);
+        for _ in 0..2_000 {
+            let x = (rng.next_u64() as u128) << rng.below(64);
+            let x = x.max(1);
+            let expected = (x as f64).log2() - 64.0;
+            let actual = log2(x) as f64 / ONE as f64;
+            assert!((actual - expected).abs() < 1e-12, "log2({}) = {} vs {}", x, actual, expected);
+        }
+        assert_eq!(log2(ONE), 0);
+        assert_eq!(log2(8 * ONE), 3 << 64);
+    }
+
+    #[test]
+    fn exp2_matches_f64() {
+        let mut rng = Rng(0xface);
+        for _ in 0..2_000 {
+            // exponents in [-40, 40)
+            let e = (rng.below(80 << 20) as i128 - (40 << 20)) << 44;
+            let expected = (e as f64 / ONE as f64).exp2();
+            let actual = to_f64(exp2(e).unwrap());
+            // Results below one also lose up to 2^-64 to the final shift
+            let tolerance = expected * 1e-14 + 2f64.powi(-63);
+            assert!((actual - expected).abs() < tolerance, "exp2 {} vs {}", actual, expected);
+        }
+        assert_eq!(exp2(0), Some(ONE));
+        assert_eq!(exp2(5 << 64), Some(32 * ONE));
+        assert_eq!(exp2(64 << 64), None);
+    }
+
+    #[test]
+    fn ln_matches_f64() {
+        let mut rng = Rng(0x1234);
+        for _ in 0..2_000 {
+            let x = ((rng.next_u64() as u128) << rng.below(64)).max(1);
+            let expected = (x as f64).ln() - 64.0 * std::f64::consts::LN_2;
+            let actual = ln(x) as f64 / ONE as f64;
+            assert!((actual - expected).abs() < 1e-12, "ln({}) = {} vs {}", x, actual, expected);
+        }
+        assert_eq!(ln(ONE), 0);
+    }
+
+    #[test]
+    fn exp_matches_f64() {
+        let mut rng = Rng(0x4321);
+        for _ in 0..2_000 {
+            // exponents in [-30, 30)
+            let x = (rng.below(60 << 20) as i128 - (30 << 20)) << 44;
+            let expected = (x as f64 / ONE as f64).exp();
+            let actual = to_f64(exp(x).unwrap());
+            let tolerance = expected * 1e-14 + 2f64.powi(-63);
+            assert!((actual - expected).abs() < tolerance, "exp {} vs {}", actual, expected);
+        }
+        assert_eq!(exp(0), Some(ONE));
+        assert_eq!(exp(-(1000 << 64)), Some(0));
+        assert_eq!(exp(50 << 64), None);
+    }
+}
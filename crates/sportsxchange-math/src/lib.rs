@@ -0,0 +1,61 @@
+// Curve math for sportsxchange, shared by the on-chain program, the Rust SDK
+// and the WASM build for the frontend so every client prices trades with the
+// exact integers the program uses.
+//
+// Units follow the program: prices are USDC base units per whole token,
+// supplies and amounts are base units (6 decimals). Everything is integer or
+// fixed-point arithmetic with no allocation, so the crate is `no_std` and
+// gives bit-identical results on BPF, native and wasm32 targets.
+//
+//   linear            price = base_price + slope * s, closed-form inverse
+//   power             price = base_price + slope * s^n, fractional n
+//   constant_product  x * y = k swaps
+//   fixed_point       Q64.64 mul, log2, exp2, ln and exp
+//
+// Rounding always favours the pool: buy costs round up, sell proceeds and
+// swap outputs round down.
+
+#![cfg_attr(not(test), no_std)]
+
+pub mod constant_product;
+mod error;
+pub mod fixed_point;
+pub mod linear;
+pub mod power;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod testing;
+
+pub use error::{MathError, Result};
+
+// Base units per whole token
+pub const TOKEN_SCALE: u128 = 1_000_000;
+
+// Integer square root (floor) via Newton's method
+pub fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+
+    let mut x = 1u128 << ((128 - n.leading_zeros()).div_ceil(2));
+    loop {
+        let y = (x + n / x) / 2;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn isqrt_floors() {
+        for n in [0u128, 1, 2, 3, 4, 15, 16, 17, 1 << 64, u64::MAX as u128, u128::MAX] {
+            let r = isqrt(n);
+            assert!(r * r <= n);
+            assert!((r + 1).checked_mul(r + 1).is_none_or(|sq| sq > n));
+        }
+    }
+}
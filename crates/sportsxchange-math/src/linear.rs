@@ -0,0 +1,176 @@
+// Linear bonding curve: price(s) = base_price + slope * s / 1_000_000
+//
+// Prices are USDC base units per whole token and supplies are token base
+// units (6 decimals), so the USDC cost of moving supply from s0 to s1 is
+// the integral of price(s) / 1_000_000 over [s0, s1]:
+//
+//   cost = (2_000_000 * base_price * (s1 - s0) + slope * (s1^2 - s0^2)) / (2 * 10^12)
+//
+// Buys and sells both go through this integral. Buys round tokens down and
+// sells round USDC down, so pool_value never drops below the integral from 0
+// to the current supply and a round trip can never pay out more than it paid in.
+
+use crate::{isqrt, MathError, Result, TOKEN_SCALE};
+
+const COST_DENOMINATOR: u128 = 2 * TOKEN_SCALE * TOKEN_SCALE;
+
+pub struct LinearCurve {
+    pub base_price: u64,
+    pub slope: u64,
+}
+
+impl LinearCurve {
+    // Marginal price at `supply`, in USDC base units per whole token
+    pub fn spot_price(&self, supply: u64) -> Result<u64> {
+        let price = (self.slope as u128)
+            .checked_mul(supply as u128)
+            .ok_or(MathError::MathOverflow)?
+            / TOKEN_SCALE
+            + self.base_price as u128;
+
+        u64::try_from(price).map_err(|_| MathError::MathOverflow)
+    }
+
+    // USDC needed to mint `amount` tokens on top of `supply`, rounded up
+    pub fn buy_cost(&self, supply: u64, amount: u64) -> Result<u64> {
+        let end = supply.checked_add(amount).ok_or(MathError::MathOverflow)?;
+        let cost = self.cost_scaled(supply as u128, end as u128)?.div_ceil(COST_DENOMINATOR);
+        u64::try_from(cost).map_err(|_| MathError::MathOverflow)
+    }
+
+    // USDC returned for burning `amount` of `supply`, rounded down
+    pub fn sell_proceeds(&self, supply: u64, amount: u64) -> Result<u64> {
+        let end_supply = supply
+            .checked_sub(amount)
+            .ok_or(MathError::InsufficientSupply)?;
+
+        let usdc_out = self.cost(end_supply as u128, supply as u128)?;
+
+        u64::try_from(usdc_out).map_err(|_| MathError::MathOverflow)
+    }
+
+    // Tokens minted for `usdc_amount` at `supply`.
+    // Solves cost(s0, s0 + tokens) = usdc_amount for tokens:
+    //
+    //   a = 10^6 * base_price + slope * s0
+    //   tokens = (sqrt(a^2 + 2 * 10^12 * slope * usdc_amount) - a) / slope
+    pub fn tokens_for_usdc(&self, supply: u64, usdc_amount: u64) -> Result<u64> {
+        let (base_price, slope) = (self.base_price, self.slope);
+        if slope == 0 {
+            if base_price == 0 {
+                return Ok(0);
+            }
+            let tokens_out = (usdc_amount as u128)
+                .checked_mul(TOKEN_SCALE)
+                .ok_or(MathError::MathOverflow)?
+                / base_price as u128;
+            return u64::try_from(tokens_out).map_err(|_| MathError::MathOverflow);
+        }
+
+        let a = (base_price as u128)
+            .checked_mul(TOKEN_SCALE)
+            .and_then(|v| v.checked_add((slope as u128).checked_mul(supply as u128)?))
+            .ok_or(MathError::MathOverflow)?;
+
+        let discriminant = a
+            .checked_mul(a)
+            .and_then(|v| {
+                v.checked_add(
+                    (slope as u128)
+                        .checked_mul(usdc_amount as u128)?
+                        .checked_mul(COST_DENOMINATOR)?,
+                )
+            })
+            .ok_or(MathError::MathOverflow)?;
+
+        // Both the root and the division round down, so tokens_out never costs
+        // more than usdc_amount along the curve.
+        let tokens_out = (isqrt(discriminant) - a) / slope as u128;
+
+        u64::try_from(tokens_out).map_err(|_| MathError::MathOverflow)
+    }
+
+    // floor(integral of price from s0 to s1), s0 <= s1
+    fn cost(&self, s0: u128, s1: u128) -> Result<u128> {
+        Ok(self.cost_scaled(s0, s1)? / COST_DENOMINATOR)
+    }
+
+    // integral of price from s0 to s1, times COST_DENOMINATOR (exact)
+    fn cost_scaled(&self, s0: u128, s1: u128) -> Result<u128> {
+        let delta = s1 - s0;
+
+        let base_term = (self.base_price as u128)
+            .checked_mul(delta)
+            .and_then(|v| v.checked_mul(2 * TOKEN_SCALE))
+            .ok_or(MathError::MathOverflow)?;
+
+        // s1^2 - s0^2 = delta * (s0 + s1)
+        let slope_term = (self.slope as u128)
+            .checked_mul(delta)
+            .and_then(|v| v.checked_mul(s0.checked_add(s1)?))
+            .ok_or(MathError::MathOverflow)?;
+
+        base_term
+            .checked_add(slope_term)
+            .ok_or(MathError::MathOverflow)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PARAMS: [(u64, u64); 4] = [(100_000, 10_000), (100_000, 0), (1, 1), (500_000, 250_000)];
+
+    #[test]
+    fn buy_spends_no_more_than_paid() {
+        for (base_price, slope) in PARAMS {
+            let curve = LinearCurve { base_price, slope };
+            for supply in [0u64, 1, 999_999, 1_000_000, 123_456_789_000] {
+                for usdc in [1u64, 1_000, 10_000_000, 1_000_000_000_000] {
+                    let tokens = curve.tokens_for_usdc(supply, usdc).unwrap();
+                    let cost = curve.cost(supply as u128, (supply + tokens) as u128).unwrap();
+                    assert!(cost <= usdc as u128);
+                    // One more token would cost more than was paid
+                    let next = curve.cost(supply as u128, (supply + tokens + 1) as u128).unwrap();
+                    assert!(next + 1 >= usdc as u128);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn round_trip_never_profits() {
+        for (base_price, slope) in PARAMS {
+            let curve = LinearCurve { base_price, slope };
+            for supply in [0u64, 5_000_000, 987_654_321] {
+                for usdc in [1u64, 7, 10_000_000, 50_000_000_000] {
+                    let tokens = curve.tokens_for_usdc(supply, usdc).unwrap();
+                    let usdc_back = curve.sell_proceeds(supply + tokens, tokens).unwrap();
+                    assert!(usdc_back <= usdc);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn pool_covers_full_unwind() {
+        for (base_price, slope) in PARAMS {
+            let curve = LinearCurve { base_price, slope };
+            let mut supply = 0u64;
+            let mut pool = 0u64;
+            for usdc in [3_000_000u64, 17, 250_000_000, 1, 42_000_000] {
+                supply += curve.tokens_for_usdc(supply, usdc).unwrap();
+                pool += usdc;
+            }
+            // Sell back in uneven chunks
+            while supply > 0 {
+                let amount = (supply / 3).max(1);
+                let usdc_out = curve.sell_proceeds(supply, amount).unwrap();
+                assert!(usdc_out <= pool);
+                pool -= usdc_out;
+                supply -= amount;
+            }
+        }
+    }
+}
@@ -0,0 +1,254 @@
+// Fixed-point power curve
+//
+//   price(x) = base_price + slope * x^n
+//
+// with x the supply in whole tokens and n = exponent / 100 (150 = 1.5), the
+// same scaling lib_v2 used for its `n`. The USDC cost of moving supply from
+// x0 to x1 is the closed-form integral
+//
+//   cost = base_price * (x1 - x0) + slope * (x1^(n+1) - x0^(n+1)) / (n + 1)
+//
+// so buys and sells cost a fixed number of fixed-point operations instead of
+// a stepwise numeric integration.
+//
+// Powers are evaluated in Q64.64 fixed point (see fixed_point.rs) as
+// x^y = exp2(y * log2(x)), accurate to a relative 2^-52 for y <= 5, plus
+// < 2^-63 absolute from the final shift when the result is below one.
+//
+// The curve never trusts a single rounded power: buy costs use an upper
+// bound of x1^(n+1) and a lower bound of x0^(n+1) (and the reverse for
+// sells), each widened by a relative 2^-48 plus two ulps, far more than the
+// error above. Buys therefore always cost at least the exact integral and
+// sells never pay more than it. The widening costs at most about
+// slope * x^(n+1) * 2^-47 base units per trade.
+//
+// x^(n+1) must stay below 2^64; past that the cost of a single trade is far
+// beyond any real USDC supply and the math reports MathOverflow.
+
+use crate::fixed_point::{exp2, log2, mul, mul_div, to_integer, ONE};
+use crate::{MathError, Result, TOKEN_SCALE};
+
+// Largest exponent markets may use: 400 = 4.0
+pub const MAX_EXPONENT: u16 = 400;
+
+// Relative widening applied to every rounded power, as a right shift
+const POW_MARGIN_SHIFT: u32 = 48;
+
+// x^(exponent / 100) in Q64.64, x in Q64.64
+fn pow(x: u128, exponent: u32) -> Result<u128> {
+    if x == 0 || exponent == 0 {
+        return Ok(if exponent == 0 { ONE } else { 0 });
+    }
+
+    let scaled = log2(x)
+        .checked_mul(exponent as i128)
+        .ok_or(MathError::MathOverflow)?
+        / 100;
+
+    exp2(scaled).ok_or(MathError::MathOverflow)
+}
+
+// Lower and upper bounds on (supply / 10^6)^(exponent / 100)
+fn pow_bounds(supply: u64, exponent: u32) -> Result<(u128, u128)> {
+    let numerator = (supply as u128) << 64;
+    let x_low = numerator / TOKEN_SCALE;
+    let x_high = numerator.div_ceil(TOKEN_SCALE);
+
+    let low = pow(x_low, exponent)?;
+    let high = pow(x_high, exponent)?;
+
+    Ok((
+        low.saturating_sub((low >> POW_MARGIN_SHIFT) + 2),
+        high.checked_add((high >> POW_MARGIN_SHIFT) + 2).ok_or(MathError::MathOverflow)?,
+    ))
+}
+
+pub struct PowerCurve {
+    pub base_price: u64,
+    pub slope: u64,
+    pub exponent: u16,  // hundredths: 150 = 1.5
+}
+
+impl PowerCurve {
+    // Bound on the integral from s0 to s1 in USDC base units: an upper bound
+    // when round_up, a lower bound otherwise
+    fn cost(&self, s0: u64, s1: u64, round_up: bool) -> Result<u64> {
+        if s0 == s1 {
+            return Ok(0);
+        }
+
+        let n1 = self.exponent as u32 + 100;
+        let (low0, high0) = pow_bounds(s0, n1)?;
+        let (low1, high1) = pow_bounds(s1, n1)?;
+        let rise = if round_up {
+            high1.saturating_sub(low0)
+        } else {
+            low1.saturating_sub(high0)
+        };
+
+        // slope * rise / (n + 1), still Q64.64; mul truncates, so round_up
+        // adds back the lost ulp
+        let power_term = mul_div(rise, 100, n1 as u128, round_up)
+            .and_then(|v| mul((self.slope as u128) << 64, v))
+            .and_then(|v| v.checked_add(round_up as u128))
+            .ok_or(MathError::MathOverflow)?;
+
+        let base_scaled = (self.base_price as u128) * ((s1 - s0) as u128);
+        let base_term = if round_up {
+            base_scaled.div_ceil(TOKEN_SCALE)
+        } else {
+            base_scaled / TOKEN_SCALE
+        };
+
+        let cost = to_integer(power_term, round_up)
+            .checked_add(base_term)
+            .ok_or(MathError::MathOverflow)?;
+        u64::try_from(cost).map_err(|_| MathError::MathOverflow)
+    }
+
+    // Marginal price at `supply`
+    pub fn spot_price(&self, supply: u64) -> Result<u64> {
+        let x = ((supply as u128) << 64) / TOKEN_SCALE;
+        let power = pow(x, self.exponent as u32)?;
+        let price = mul((self.slope as u128) << 64, power)
+            .map(|v| v >> 64)
+            .and_then(|v| v.checked_add(self.base_price as u128))
+            .ok_or(MathError::MathOverflow)?;
+        u64::try_from(price).map_err(|_| MathError::MathOverflow)
+    }
+
+    // USDC needed to mint `amount` tokens on top of `supply`, rounded up
+    pub fn buy_cost(&self, supply: u64, amount: u64) -> Result<u64> {
+        let end = supply.checked_add(amount).ok_or(MathError::MathOverflow)?;
+        self.cost(supply, end, true)
+    }
+
+    // USDC paid for burning `amount` of `supply`, rounded down
+    pub fn sell_proceeds(&self, supply: u64, amount: u64) -> Result<u64> {
+        let start = supply.checked_sub(amount).ok_or(MathError::InsufficientSupply)?;
+        self.cost(start, supply, false)
+    }
+
+    // Newton's method from above. For a rising price, stepping back from an
+    // unaffordable `hi` by (cost(hi) - usdc) / price(hi) lands at or just
+    // below the answer, so `hi` falls onto it in a handful of iterations and
+    // a short walk up fixes any rounding.
+    pub fn tokens_for_usdc(&self, supply: u64, usdc_amount: u64) -> Result<u64> {
+        let affordable = |amount: u64| {
+            self.buy_cost(supply, amount).is_ok_and(|cost| cost <= usdc_amount)
+        };
+
+        let mut hi = u64::MAX - supply;
+        let price = self.spot_price(supply)?;
+        if price > 0 {
+            let bound = (usdc_amount as u128) * TOKEN_SCALE / price as u128;
+            hi = hi.min(u64::try_from(bound).unwrap_or(u64::MAX));
+        }
+
+        while hi > 0 && !affordable(hi) {
+            let excess = match self.buy_cost(supply, hi) {
+                Ok(cost) => cost - usdc_amount,
+                Err(_) => {
+                    // Too far out to even price; halve and retry
+                    hi /= 2;
+                    continue;
+                }
+            };
+            let price = self.spot_price(supply + hi)?.max(1);
+            let step = ((excess as u128) * TOKEN_SCALE / price as u128).max(1);
+            hi = hi.saturating_sub(u64::try_from(step).unwrap_or(u64::MAX));
+        }
+
+        // Rounding can leave the estimate a token or two short
+        while hi < u64::MAX - supply && affordable(hi + 1) {
+            hi += 1;
+        }
+
+        Ok(hi)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{from_f64, to_f64, Rng};
+
+    #[test]
+    fn pow_matches_f64() {
+        let mut rng = Rng(0xbeef);
+        for _ in 0..2_000 {
+            let x = rng.below(10_000_000) as f64 / 1_000.0 + 0.001;
+            let exponent = 1 + rng.below(500) as u32;
+            let expected = x.powf(exponent as f64 / 100.0);
+            if expected >= 1.8e19 {
+                continue;
+            }
+            let actual = to_f64(pow(from_f64(x), exponent).unwrap());
+            let error = (actual - expected).abs() / expected.max(1.0);
+            assert!(error < 1e-12, "{}^{} = {} vs {}", x, exponent, actual, expected);
+        }
+    }
+
+    #[test]
+    fn pow_bounds_bracket_f64() {
+        let mut rng = Rng(0xcafe);
+        for _ in 0..2_000 {
+            let supply = rng.below(1_000_000_000_000);
+            let exponent = 1 + rng.below(500) as u32;
+            let expected = (supply as f64 / 1e6).powf(exponent as f64 / 100.0);
+            if expected >= 1.8e19 {
+                continue;
+            }
+            let (low, high) = pow_bounds(supply, exponent).unwrap();
+            // f64 itself is only good to ~1e-16 relative
+            assert!(to_f64(low) <= expected * (1.0 + 1e-15) + 1e-18);
+            assert!(to_f64(high) >= expected * (1.0 - 1e-15));
+        }
+    }
+
+    #[test]
+    fn cost_brackets_f64_integral() {
+        let mut rng = Rng(0xd00d);
+        for _ in 0..1_000 {
+            let curve = PowerCurve {
+                base_price: rng.below(1_000_000),
+                slope: 1 + rng.below(100_000),
+                exponent: 1 + rng.below(MAX_EXPONENT as u64) as u16,
+            };
+            let s0 = rng.below(10_000_000_000);
+            let s1 = s0 + rng.below(10_000_000_000);
+            let n1 = curve.exponent as f64 / 100.0 + 1.0;
+            let (x0, x1) = (s0 as f64 / 1e6, s1 as f64 / 1e6);
+            let exact = curve.base_price as f64 * (x1 - x0)
+                + curve.slope as f64 * (x1.powf(n1) - x0.powf(n1)) / n1;
+            if x1.powf(n1) >= 1.8e19 || exact >= 1.8e19 {
+                continue;
+            }
+
+            let upper = curve.cost(s0, s1, true).unwrap() as f64;
+            let lower = curve.cost(s0, s1, false).unwrap() as f64;
+            let tolerance = exact * 1e-12 + 1.0;
+            assert!(lower <= exact + tolerance && upper >= exact - tolerance);
+            // The widening stays a rounding-level effect
+            assert!(upper - lower <= exact * 1e-9 + 4.0, "{} .. {} for {}", lower, upper, exact);
+        }
+    }
+
+    #[test]
+    fn tokens_for_usdc_is_maximal() {
+        let mut rng = Rng(0xabcd);
+        for _ in 0..300 {
+            let curve = PowerCurve {
+                base_price: rng.below(1_000_000),
+                slope: 1 + rng.below(100_000),
+                exponent: 1 + rng.below(MAX_EXPONENT as u64) as u16,
+            };
+            let supply = rng.below(1_000_000_000);
+            let usdc = 1 + rng.below(100_000_000_000);
+            let tokens = curve.tokens_for_usdc(supply, usdc).unwrap();
+            assert!(curve.buy_cost(supply, tokens).unwrap() <= usdc);
+            // One more token either costs too much or cannot be priced at all
+            assert!(!curve.buy_cost(supply, tokens + 1).is_ok_and(|c| c <= usdc));
+        }
+    }
+}
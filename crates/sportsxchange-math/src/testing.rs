@@ -0,0 +1,30 @@
+// Helpers for property tests against f64 references, here and in crates
+// built on this one (enable the `test-utils` feature)
+
+use crate::fixed_point::ONE;
+
+// Deterministic xorshift so the property tests need no extra crates
+pub struct Rng(pub u64);
+
+impl Rng {
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    pub fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+}
+
+// Q64.64 to f64
+pub fn to_f64(value: u128) -> f64 {
+    value as f64 / ONE as f64
+}
+
+// f64 to Q64.64, truncated
+pub fn from_f64(value: f64) -> u128 {
+    (value * ONE as f64) as u128
+}
@@ -2,454 +2,4782 @@ use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Mint, Token, TokenAccount, MintTo, Burn, Transfer};
 use anchor_spl::associated_token::AssociatedToken;
 
+mod lmsr;
+
+use lmsr::LmsrCurve;
+use sportsxchange_math::linear::LinearCurve;
+use sportsxchange_math::power::{self, PowerCurve};
+use sportsxchange_math::{isqrt, MathError, TOKEN_SCALE};
+
 declare_id!("7ahGrFV9AttAdvq3mdfofVLgTSnqzwmZVfCHY6xy1cUH");
 
-#[program]
-pub mod sportsxchange {
-    use super::*;
+// anchor 0.31's #[program] expansion still calls the deprecated AccountInfo::realloc.
+// The expansion lands beside the annotated module, so it is wrapped here to keep
+// the allow off the rest of the crate.
+#[allow(deprecated)]
+mod instructions {
+    use super::*;
+
+    #[program]
+    pub mod sportsxchange {
+        use super::*;
+
+        // One-time protocol setup; only the program's upgrade authority may call it
+        pub fn initialize_config(ctx: Context<InitializeConfig>) -> Result<()> {
+            let config = &mut ctx.accounts.config;
+            let admin = ctx.accounts.admin.key();
+            config.admin = admin;
+            config.market_creator = admin;
+            config.resolver = admin;
+            config.pauser = admin;
+            config.paused = false;
+            config.protocol_fee_bps = 0;
+            config.creator_fee_bps = 0;
+            config.treasury = admin;
+            config.arbiter = admin;
+            config.resolution_bond = ProtocolConfig::DEFAULT_RESOLUTION_BOND;
+            config.dispute_window_secs = ProtocolConfig::DEFAULT_DISPUTE_WINDOW_SECS;
+            config.allowed_collateral_mints = Vec::new();
+            config.bump = ctx.bumps.config;
+
+            msg!("Protocol config initialized. Admin: {}", config.admin);
+            emit_config_updated(config)?;
+
+            Ok(())
+        }
+
+        // Allow markets to be created against a collateral mint
+        pub fn add_collateral_mint(ctx: Context<UpdateConfig>, mint: Pubkey) -> Result<()> {
+            let config = &mut ctx.accounts.config;
+
+            require!(
+                !config.allowed_collateral_mints.contains(&mint),
+                ErrorCode::CollateralAlreadyAllowed
+            );
+            require!(
+                config.allowed_collateral_mints.len() < ProtocolConfig::MAX_COLLATERAL_MINTS,
+                ErrorCode::CollateralListFull
+            );
+
+            config.allowed_collateral_mints.push(mint);
+
+            msg!("Collateral mint allowed: {}", mint);
+            emit_config_updated(config)?;
+
+            Ok(())
+        }
+
+        // Stop new markets from using a collateral mint. Existing markets keep
+        // the mint they were created with.
+        pub fn remove_collateral_mint(ctx: Context<UpdateConfig>, mint: Pubkey) -> Result<()> {
+            let config = &mut ctx.accounts.config;
+
+            let index = config
+                .allowed_collateral_mints
+                .iter()
+                .position(|m| *m == mint)
+                .ok_or(ErrorCode::CollateralNotAllowed)?;
+            config.allowed_collateral_mints.swap_remove(index);
+
+            msg!("Collateral mint removed: {}", mint);
+            emit_config_updated(config)?;
+
+            Ok(())
+        }
+
+        // Rotate any of the protocol role keys. Fields left as None are unchanged.
+        pub fn update_roles(
+            ctx: Context<UpdateConfig>,
+            admin: Option<Pubkey>,
+            market_creator: Option<Pubkey>,
+            resolver: Option<Pubkey>,
+            pauser: Option<Pubkey>,
+            arbiter: Option<Pubkey>,
+        ) -> Result<()> {
+            let config = &mut ctx.accounts.config;
+
+            if let Some(admin) = admin {
+                config.admin = admin;
+            }
+            if let Some(market_creator) = market_creator {
+                config.market_creator = market_creator;
+            }
+            if let Some(resolver) = resolver {
+                config.resolver = resolver;
+            }
+            if let Some(pauser) = pauser {
+                config.pauser = pauser;
+            }
+            if let Some(arbiter) = arbiter {
+                config.arbiter = arbiter;
+            }
+
+            msg!("Roles updated: admin={}, market_creator={}, resolver={}, pauser={}, arbiter={}",
+                config.admin, config.market_creator, config.resolver, config.pauser, config.arbiter
+            );
+            emit_config_updated(config)?;
+
+            Ok(())
+        }
+
+        // Trading fees in basis points. Markets take a copy at creation, so a
+        // change only affects markets created afterwards.
+        pub fn set_fees(
+            ctx: Context<UpdateConfig>,
+            protocol_fee_bps: u16,
+            creator_fee_bps: u16,
+        ) -> Result<()> {
+            require!(
+                protocol_fee_bps as u32 + creator_fee_bps as u32 <= ProtocolConfig::MAX_TOTAL_FEE_BPS as u32,
+                ErrorCode::FeeTooHigh
+            );
+
+            let config = &mut ctx.accounts.config;
+            config.protocol_fee_bps = protocol_fee_bps;
+            config.creator_fee_bps = creator_fee_bps;
+
+            msg!("Fees set: protocol={}bps, creator={}bps", protocol_fee_bps, creator_fee_bps);
+            emit_config_updated(config)?;
+
+            Ok(())
+        }
+
+        // Owner of the token accounts protocol fees are withdrawn to
+        pub fn set_treasury(ctx: Context<UpdateConfig>, treasury: Pubkey) -> Result<()> {
+            let config = &mut ctx.accounts.config;
+            config.treasury = treasury;
+
+            msg!("Treasury set: {}", treasury);
+            emit_config_updated(config)?;
+
+            Ok(())
+        }
+
+        // Bond every optimistic proposal and dispute must post, and how long a
+        // proposal stays open to dispute. Proposals already made keep their terms.
+        pub fn set_resolution_params(
+            ctx: Context<UpdateConfig>,
+            resolution_bond: u64,
+            dispute_window_secs: i64,
+        ) -> Result<()> {
+            require!(
+                resolution_bond > 0 && dispute_window_secs > 0,
+                ErrorCode::InvalidResolutionParams
+            );
+
+            let config = &mut ctx.accounts.config;
+            config.resolution_bond = resolution_bond;
+            config.dispute_window_secs = dispute_window_secs;
+
+            msg!("Resolution bond {} USDC, dispute window {}s", resolution_bond, dispute_window_secs);
+            emit_config_updated(config)?;
+
+            Ok(())
+        }
+
+        // Global kill switch for market creation, trading and claims
+        pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+            let config = &mut ctx.accounts.config;
+            config.paused = paused;
+
+            msg!("Protocol {}", if paused { "paused" } else { "unpaused" });
+            emit_config_updated(config)?;
+
+            Ok(())
+        }
+
+        // Create market with simpler linear bonding curve
+        #[allow(clippy::too_many_arguments)]
+        pub fn create_market_v2(
+            ctx: Context<CreateMarketV2>,
+            game_id: String,
+            team_a: String,
+            team_b: String,
+            base_price: u64,  // Base price in lamports per token (e.g., 100 = 0.0001 SOL)
+            slope: u64,       // Price increase per million tokens (e.g., 10 = price goes up 0.00001 SOL per million tokens)
+            curve_type: CurveType,
+            kind: MarketKind,  // What team A and team B tokens stand for
+            trading_close_ts: i64,  // Game kickoff; trading stops automatically from then on
+            oracle: Option<Pubkey>,  // Publisher whose OracleResult may settle the market
+        ) -> Result<()> {
+            let clock = Clock::get()?;
+            require!(trading_close_ts > clock.unix_timestamp, ErrorCode::InvalidCloseTime);
+            curve_type.validate(base_price, slope)?;
+            kind.validate()?;
+            let subsidy = curve_type.subsidy()?;
+
+            // LMSR markets are seeded by the creator; the subsidy bounds their loss
+            if subsidy > 0 {
+                let authority_usdc = ctx
+                    .accounts
+                    .authority_usdc
+                    .as_ref()
+                    .ok_or(ErrorCode::SubsidyAccountRequired)?;
+                let cpi_ctx = CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: authority_usdc.to_account_info(),
+                        to: ctx.accounts.usdc_vault.to_account_info(),
+                        authority: ctx.accounts.authority.to_account_info(),
+                    },
+                );
+                token::transfer(cpi_ctx, subsidy)?;
+            }
+
+            let market = &mut ctx.accounts.market;
+            market.authority = ctx.accounts.authority.key();
+            market.game_id = game_id;
+            market.team_a = team_a;
+            market.team_b = team_b;
+            market.team_a_mint = ctx.accounts.team_a_mint.key();
+            market.team_b_mint = ctx.accounts.team_b_mint.key();
+            market.usdc_vault = ctx.accounts.usdc_vault.key();
+            market.usdc_mint = ctx.accounts.usdc_mint.key();
+            market.usdc_decimals = ctx.accounts.usdc_mint.decimals;
+            market.fee_vault = ctx.accounts.fee_vault.key();
+            market.protocol_fee_bps = ctx.accounts.config.protocol_fee_bps;
+            market.creator_fee_bps = ctx.accounts.config.creator_fee_bps;
+            market.protocol_fees_accrued = 0;
+            market.creator_fees_accrued = 0;
+            market.curve_type = curve_type;
+            market.kind = kind;
+            market.base_price = base_price;
+            market.slope = slope;
+            market.team_a_supply = 0;
+            market.team_b_supply = 0;
+            market.pool_value = subsidy;
+            market.team_a_pool = 0;
+            market.team_b_pool = 0;
+            market.subsidy = subsidy;
+            market.subsidy_surplus = 0;
+            market.is_resolved = false;
+            market.is_voided = false;
+            market.winner = None;
+            market.trading_close_ts = trading_close_ts;
+            market.oracle = oracle;
+            market.resolution_state = ResolutionState::Idle;
+            market.scalar_value = None;
+            market.trading_halted = false;
+        
+            let price = market.curve(0).spot_price(0)?;
+            emit!(MarketCreatedEvent {
+                version: EVENT_VERSION,
+                market: market.key(),
+                authority: market.authority,
+                game_id: market.game_id.clone(),
+                team_a: market.team_a.clone(),
+                team_b: market.team_b.clone(),
+                usdc_mint: market.usdc_mint,
+                curve_type,
+                kind,
+                base_price,
+                slope,
+                protocol_fee_bps: market.protocol_fee_bps,
+                creator_fee_bps: market.creator_fee_bps,
+                trading_close_ts,
+                oracle,
+                team_a_price: price,
+                team_b_price: price,
+                team_a_supply: 0,
+                team_b_supply: 0,
+                slot: clock.slot,
+                timestamp: clock.unix_timestamp,
+            });
+        
+            msg!("Market created: {} vs {}", market.team_a, market.team_b);
+            msg!("{:?} bonding curve: base_price={}, slope={}", curve_type, base_price, slope);
+            if subsidy > 0 {
+                msg!("Creator subsidy: {} USDC", subsidy);
+            }
+        
+            Ok(())
+        }
+
+        // Buy tokens with linear bonding curve
+        pub fn buy_on_curve(
+            ctx: Context<BuyOnCurve>,
+            team: u8,
+            usdc_amount: u64,
+            min_tokens_out: u64,
+            deadline_unix_ts: Option<i64>,
+        ) -> Result<()> {
+            check_deadline(deadline_unix_ts)?;
+        
+            let market = &ctx.accounts.market;
+        
+            // The same math `quote` previews, so a simulated quote matches the fill
+            let now = Clock::get()?.unix_timestamp;
+            let quote = quote_trade(market, team, TradeAction::Buy, usdc_amount, false, now)?;
+            let tokens_out = quote.tokens_out;
+        
+            require!(tokens_out >= min_tokens_out, ErrorCode::SlippageExceeded);
+        
+            execute_buy(ctx.accounts, ctx.bumps.market, &quote)
+        }
+
+        // Buy exactly `token_amount` tokens, paying the curve integral for them
+        // plus fees, and at most `max_usdc_in` in total
+        pub fn buy_exact_tokens(
+            ctx: Context<BuyOnCurve>,
+            team: u8,
+            token_amount: u64,
+            max_usdc_in: u64,
+            deadline_unix_ts: Option<i64>,
+        ) -> Result<()> {
+            check_deadline(deadline_unix_ts)?;
+
+            require!(token_amount > 0, ErrorCode::ZeroAmount);
+
+            let now = Clock::get()?.unix_timestamp;
+            let quote = quote_trade(&ctx.accounts.market, team, TradeAction::Buy, token_amount, true, now)?;
+
+            require!(quote.amount <= max_usdc_in, ErrorCode::SlippageExceeded);
+
+            execute_buy(ctx.accounts, ctx.bumps.market, &quote)
+        }
+
+        // Preview a trade without executing it. `amount` is the USDC spent for
+        // a buy and the tokens sold for a sell, or with `exact_output` the tokens
+        // bought and the USDC received. Runs the exact math of buy_on_curve /
+        // sell_on_curve (buy_exact_tokens / sell_for_exact_usdc) and fails the
+        // same way they would; the TradeQuote comes back as return data, so
+        // clients read it by simulating the transaction.
+        pub fn quote(
+            ctx: Context<Quote>,
+            team: u8,
+            action: TradeAction,
+            amount: u64,
+            exact_output: bool,
+        ) -> Result<TradeQuote> {
+            let now = Clock::get()?.unix_timestamp;
+            quote_trade(&ctx.accounts.market, team, action, amount, exact_output, now)
+        }
+
+        // Sell tokens back to the bonding curve
+        pub fn sell_on_curve(
+            ctx: Context<SellOnCurve>,
+            team: u8,
+            token_amount: u64,
+            min_usdc_out: u64,
+            deadline_unix_ts: Option<i64>,
+        ) -> Result<()> {
+            check_deadline(deadline_unix_ts)?;
+        
+            let market = &ctx.accounts.market;
+        
+            // The same math `quote` previews, so a simulated quote matches the fill
+            let now = Clock::get()?.unix_timestamp;
+            let quote = quote_trade(market, team, TradeAction::Sell, token_amount, false, now)?;
+            let net_out = quote.usdc_out;
+        
+            require!(net_out >= min_usdc_out, ErrorCode::SlippageExceeded);
+        
+            execute_sell(ctx.accounts, ctx.bumps.market, &quote)
+        }
+
+        // Sell the fewest tokens that return exactly `usdc_amount` after fees,
+        // burning at most `max_tokens_in`
+        pub fn sell_for_exact_usdc(
+            ctx: Context<SellOnCurve>,
+            team: u8,
+            usdc_amount: u64,
+            max_tokens_in: u64,
+            deadline_unix_ts: Option<i64>,
+        ) -> Result<()> {
+            check_deadline(deadline_unix_ts)?;
+
+            require!(usdc_amount > 0, ErrorCode::ZeroAmount);
+
+            let now = Clock::get()?.unix_timestamp;
+            let quote = quote_trade(&ctx.accounts.market, team, TradeAction::Sell, usdc_amount, true, now)?;
+
+            require!(quote.amount <= max_tokens_in, ErrorCode::SlippageExceeded);
+
+            execute_sell(ctx.accounts, ctx.bumps.market, &quote)
+        }
+
+        // Deposit `amount` USDC for `amount` team A plus `amount` team B tokens.
+        // Complete sets sit outside the curves: they don't move prices and their
+        // collateral is held apart from pool_value until settlement. Only LMSR
+        // pays a winning token exactly one USDC, so only LMSR markets take
+        // sets; a bonding curve can pay more and the sets would dilute its
+        // winners. Minting stops with trading, once the result may be known.
+        pub fn mint_complete_set(ctx: Context<CompleteSet>, amount: u64) -> Result<()> {
+            let market = &ctx.accounts.market;
+            let now = Clock::get()?.unix_timestamp;
+
+            require!(amount > 0, ErrorCode::ZeroAmount);
+            require!(market.is_lmsr(), ErrorCode::CompleteSetsRequireLmsr);
+            require!(!market.is_resolved, ErrorCode::AlreadyResolved);
+            require!(!market.is_voided, ErrorCode::MarketVoided);
+            require!(!market.trading_halted, ErrorCode::TradingHalted);
+            require!(now < market.trading_close_ts, ErrorCode::TradingClosed);
+
+            let cpi_ctx = CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.user_usdc.to_account_info(),
+                    to: ctx.accounts.usdc_vault.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            );
+            token::transfer(cpi_ctx, amount)?;
+
+            let seeds = &[
+                b"market",
+                market.game_id.as_bytes(),
+                &[ctx.bumps.market],
+            ];
+            let signer = &[&seeds[..]];
+
+            for (mint, token_account) in [
+                (&ctx.accounts.team_a_mint, &ctx.accounts.user_team_a_account),
+                (&ctx.accounts.team_b_mint, &ctx.accounts.user_team_b_account),
+            ] {
+                let cpi_ctx = CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    MintTo {
+                        mint: mint.to_account_info(),
+                        to: token_account.to_account_info(),
+                        authority: market.to_account_info(),
+                    },
+                    signer,
+                );
+                token::mint_to(cpi_ctx, amount)?;
+            }
+
+            let market = &mut ctx.accounts.market;
+            market.complete_sets = market
+                .complete_sets
+                .checked_add(amount)
+                .ok_or(ErrorCode::MathOverflow)?;
+
+            let clock = Clock::get()?;
+            emit!(CompleteSetEvent {
+                version: EVENT_VERSION,
+                market: market.key(),
+                user: ctx.accounts.user.key(),
+                action: CompleteSetAction::Mint,
+                amount,
+                complete_sets: market.complete_sets,
+                slot: clock.slot,
+                timestamp: clock.unix_timestamp,
+            });
+
+            msg!("Minted {} complete sets", amount);
+
+            Ok(())
+        }
+
+        // Burn `amount` team A plus `amount` team B tokens for `amount` USDC.
+        // Only the collateral of outstanding complete sets backs redemptions, so
+        // at most `complete_sets` can be redeemed.
+        pub fn redeem_complete_set(ctx: Context<CompleteSet>, amount: u64) -> Result<()> {
+            let market = &ctx.accounts.market;
+
+            require!(amount > 0, ErrorCode::ZeroAmount);
+            require!(!market.is_resolved, ErrorCode::AlreadyResolved);
+            require!(!market.is_voided, ErrorCode::MarketVoided);
+            require!(amount <= market.complete_sets, ErrorCode::InsufficientCompleteSets);
+
+            for (mint, token_account) in [
+                (&ctx.accounts.team_a_mint, &ctx.accounts.user_team_a_account),
+                (&ctx.accounts.team_b_mint, &ctx.accounts.user_team_b_account),
+            ] {
+                let cpi_ctx = CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Burn {
+                        mint: mint.to_account_info(),
+                        from: token_account.to_account_info(),
+                        authority: ctx.accounts.user.to_account_info(),
+                    },
+                );
+                token::burn(cpi_ctx, amount)?;
+            }
+
+            let seeds = &[
+                b"market",
+                market.game_id.as_bytes(),
+                &[ctx.bumps.market],
+            ];
+            let signer = &[&seeds[..]];
+
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.usdc_vault.to_account_info(),
+                    to: ctx.accounts.user_usdc.to_account_info(),
+                    authority: market.to_account_info(),
+                },
+                signer,
+            );
+            token::transfer(cpi_ctx, amount)?;
+
+            let market = &mut ctx.accounts.market;
+            market.complete_sets -= amount;
+
+            let clock = Clock::get()?;
+            emit!(CompleteSetEvent {
+                version: EVENT_VERSION,
+                market: market.key(),
+                user: ctx.accounts.user.key(),
+                action: CompleteSetAction::Redeem,
+                amount,
+                complete_sets: market.complete_sets,
+                slot: clock.slot,
+                timestamp: clock.unix_timestamp,
+            });
+
+            msg!("Redeemed {} complete sets", amount);
+
+            Ok(())
+        }
+
+        // Opt in to on-chain position tracking for one market. Trades that pass
+        // the position account keep it up to date; trades that omit it still work.
+        pub fn open_position(ctx: Context<OpenPosition>) -> Result<()> {
+            let position = &mut ctx.accounts.position;
+            position.market = ctx.accounts.market.key();
+            position.user = ctx.accounts.user.key();
+            position.bump = ctx.bumps.position;
+
+            msg!("Opened position for {}", position.user);
+
+            Ok(())
+        }
+
+        // Close a position account and return its rent to the user
+        pub fn close_position(_ctx: Context<ClosePosition>) -> Result<()> {
+            Ok(())
+        }
+
+        // Halt trading when game starts
+        pub fn halt_trading(ctx: Context<HaltTrading>) -> Result<()> {
+            let market = &mut ctx.accounts.market;
+
+            require!(!market.trading_halted, ErrorCode::AlreadyHalted);
+
+            market.trading_halted = true;
+
+            let clock = Clock::get()?;
+            emit!(TradingHaltedEvent {
+                version: EVENT_VERSION,
+                market: market.key(),
+                authority: ctx.accounts.authority.key(),
+                team_a_price: market.curve(0).spot_price(market.team_a_supply)?,
+                team_b_price: market.curve(1).spot_price(market.team_b_supply)?,
+                team_a_supply: market.team_a_supply,
+                team_b_supply: market.team_b_supply,
+                pool_value: market.pool_value,
+                slot: clock.slot,
+                timestamp: clock.unix_timestamp,
+            });
+
+            msg!("Trading halted for market: {}", market.game_id);
+
+            Ok(())
+        }
+
+        // Push a market's close time back when its game is delayed. Reopens
+        // trading if the old close time has already passed.
+        pub fn extend_trading_close(ctx: Context<ExtendTradingClose>, trading_close_ts: i64) -> Result<()> {
+            let market = &mut ctx.accounts.market;
+            let clock = Clock::get()?;
+
+            require!(!market.is_resolved, ErrorCode::AlreadyResolved);
+            require!(!market.is_voided, ErrorCode::MarketVoided);
+            require!(
+                trading_close_ts > market.trading_close_ts && trading_close_ts > clock.unix_timestamp,
+                ErrorCode::InvalidCloseTime
+            );
+
+            let previous_close_ts = market.trading_close_ts;
+            market.trading_close_ts = trading_close_ts;
+
+            emit!(TradingCloseExtendedEvent {
+                version: EVENT_VERSION,
+                market: market.key(),
+                authority: ctx.accounts.authority.key(),
+                previous_close_ts,
+                trading_close_ts,
+                slot: clock.slot,
+                timestamp: clock.unix_timestamp,
+            });
+
+            msg!("Trading for {} now closes at {}", market.game_id, trading_close_ts);
+
+            Ok(())
+        }
+
+        // Resolve market with winner
+        pub fn resolve_market(
+            ctx: Context<ResolveMarket>,
+            winner: u8,  // 0 for team A, 1 for team B, 2 for a draw
+        ) -> Result<()> {
+            settle_market(&mut ctx.accounts.market, ctx.accounts.resolver.key(), winner)
+        }
+
+        // Resolve from the final score. The market's kind decides which side won;
+        // a push on a spread or total line voids the market for refunds.
+        pub fn resolve_with_scores(
+            ctx: Context<ResolveMarket>,
+            home_score: u16,
+            away_score: u16,
+        ) -> Result<()> {
+            settle_scores(&mut ctx.accounts.market, ctx.accounts.resolver.key(), home_score, away_score)
+        }
+
+        // Resolve a scalar market at the reported value. Long (team A) holders
+        // share the pool in proportion to how far the value sits from `lower`
+        // towards `upper`, short (team B) holders the rest.
+        pub fn resolve_scalar(ctx: Context<ResolveMarket>, value: i64) -> Result<()> {
+            let market = &mut ctx.accounts.market;
+            let MarketKind::Scalar { lower, upper } = market.kind else {
+                return err!(ErrorCode::InvalidMarketKind);
+            };
+            check_settleable(market)?;
+
+            // Values outside the range pay out as its nearest end
+            let clamped = value.clamp(lower, upper);
+            let share = ((clamped as i128 - lower as i128) as u64, (upper as i128 - lower as i128) as u64);
+            let winner = match clamped {
+                v if v == upper => 0,
+                v if v == lower => 1,
+                _ => MarketV2::DRAW,
+            };
+
+            market.scalar_value = Some(value);
+            msg!("Scalar value: {} ({}/{} to long)", value, share.0, share.1);
+            settle_pools(market, ctx.accounts.resolver.key(), winner, share)
+        }
+
+        // Publish a game's result feed. Whoever signs is the publisher; a market
+        // only trusts the publisher it named as its oracle.
+        pub fn publish_oracle_result(
+            ctx: Context<PublishOracleResult>,
+            game_id: String,
+            home_score: u16,
+            away_score: u16,
+            status: GameStatus,
+        ) -> Result<()> {
+            let result = &mut ctx.accounts.oracle_result;
+            result.game_id = game_id;
+            result.publisher = ctx.accounts.publisher.key();
+            result.bump = ctx.bumps.oracle_result;
+            result.record(home_score, away_score, status)?;
+
+            emit_oracle_result(result)
+        }
+
+        // Publish new scores or status for an existing feed. Final results are
+        // frozen so a settled market can't be contradicted.
+        pub fn update_oracle_result(
+            ctx: Context<UpdateOracleResult>,
+            home_score: u16,
+            away_score: u16,
+            status: GameStatus,
+        ) -> Result<()> {
+            let result = &mut ctx.accounts.oracle_result;
+            require!(result.status != GameStatus::Final, ErrorCode::OracleResultFinal);
+            result.record(home_score, away_score, status)?;
+
+            emit_oracle_result(result)
+        }
+
+        // Permissionless: anyone may settle a market from its oracle's final result
+        pub fn resolve_from_oracle(ctx: Context<ResolveFromOracle>) -> Result<()> {
+            let result = &ctx.accounts.oracle_result;
+            require!(result.status == GameStatus::Final, ErrorCode::OracleResultNotFinal);
+
+            settle_scores(&mut ctx.accounts.market, result.publisher, result.home_score, result.away_score)
+        }
+
+        // Optimistic resolution: anyone may propose the outcome by posting the
+        // config's bond. Unless disputed within the dispute window it becomes
+        // the result.
+        pub fn propose_outcome(ctx: Context<ProposeOutcome>, outcome: u8) -> Result<()> {
+            let market = &mut ctx.accounts.market;
+            let config = &ctx.accounts.config;
+            let clock = Clock::get()?;
+
+            require!(market.is_trading_closed(clock.unix_timestamp), ErrorCode::TradingNotHalted);
+            require!(!market.is_resolved, ErrorCode::AlreadyResolved);
+            require!(!market.is_voided, ErrorCode::MarketVoided);
+            market.check_resolution_idle()?;
+            market.check_outcome(outcome)?;
+
+            let bond = config.resolution_bond;
+            let cpi_ctx = CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.proposer_usdc.to_account_info(),
+                    to: ctx.accounts.bond_vault.to_account_info(),
+                    authority: ctx.accounts.proposer.to_account_info(),
+                },
+            );
+            token::transfer(cpi_ctx, bond)?;
+
+            let proposal = &mut ctx.accounts.proposal;
+            proposal.market = market.key();
+            proposal.proposer = ctx.accounts.proposer.key();
+            proposal.outcome = outcome;
+            proposal.bond = bond;
+            proposal.dispute_deadline = clock
+                .unix_timestamp
+                .checked_add(config.dispute_window_secs)
+                .ok_or(ErrorCode::MathOverflow)?;
+            proposal.disputer = None;
+            proposal.bump = ctx.bumps.proposal;
+            market.resolution_state = ResolutionState::Proposed;
+
+            emit_resolution_event(market.key(), proposal, ResolutionAction::Proposed, proposal.proposer, outcome)?;
+
+            msg!("Outcome {} proposed, disputable until {}", outcome, proposal.dispute_deadline);
+
+            Ok(())
+        }
+
+        // Challenge a proposal inside its dispute window by matching its bond.
+        // The market then waits for the arbiter.
+        pub fn dispute_outcome(ctx: Context<DisputeOutcome>) -> Result<()> {
+            let market = &mut ctx.accounts.market;
+            let proposal = &mut ctx.accounts.proposal;
+
+            require!(market.resolution_state == ResolutionState::Proposed, ErrorCode::NoProposal);
+            require!(
+                Clock::get()?.unix_timestamp < proposal.dispute_deadline,
+                ErrorCode::DisputeWindowClosed
+            );
+
+            let cpi_ctx = CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.disputer_usdc.to_account_info(),
+                    to: ctx.accounts.bond_vault.to_account_info(),
+                    authority: ctx.accounts.disputer.to_account_info(),
+                },
+            );
+            token::transfer(cpi_ctx, proposal.bond)?;
+
+            let disputer = ctx.accounts.disputer.key();
+            proposal.disputer = Some(disputer);
+            market.resolution_state = ResolutionState::Disputed;
+
+            emit_resolution_event(market.key(), proposal, ResolutionAction::Disputed, disputer, proposal.outcome)?;
+
+            msg!("Proposal disputed by {}", disputer);
+
+            Ok(())
+        }
+
+        // Permissionless: once the dispute window has passed, an undisputed
+        // proposal becomes the result and the proposer gets the bond back
+        pub fn finalize_outcome(ctx: Context<FinalizeOutcome>) -> Result<()> {
+            let proposal = &ctx.accounts.proposal;
+
+            require!(
+                ctx.accounts.market.resolution_state == ResolutionState::Proposed,
+                ErrorCode::NoProposal
+            );
+            require!(
+                Clock::get()?.unix_timestamp >= proposal.dispute_deadline,
+                ErrorCode::DisputeWindowOpen
+            );
+
+            let market = &mut ctx.accounts.market;
+            let seeds = &[
+                b"market",
+                market.game_id.as_bytes(),
+                &[ctx.bumps.market],
+            ];
+            transfer_from_vault(
+                market.to_account_info(),
+                seeds,
+                &ctx.accounts.bond_vault,
+                &ctx.accounts.proposer_usdc,
+                &ctx.accounts.token_program,
+                proposal.bond,
+            )?;
+
+            emit_resolution_event(market.key(), proposal, ResolutionAction::Finalized, proposal.proposer, proposal.outcome)?;
+
+            market.resolution_state = ResolutionState::Idle;
+            settle_market(market, proposal.proposer, proposal.outcome)
+        }
+
+        // The arbiter decides a disputed proposal and keeps both bonds
+        pub fn arbitrate_outcome(ctx: Context<ArbitrateOutcome>, outcome: u8) -> Result<()> {
+            require!(
+                ctx.accounts.market.resolution_state == ResolutionState::Disputed,
+                ErrorCode::NotDisputed
+            );
+
+            award_bonds_to_arbiter(ctx.accounts, ctx.bumps.market)?;
+
+            let market = &mut ctx.accounts.market;
+            let arbiter = ctx.accounts.arbiter.key();
+            emit_resolution_event(market.key(), &ctx.accounts.proposal, ResolutionAction::Arbitrated, arbiter, outcome)?;
+
+            market.resolution_state = ResolutionState::Idle;
+            settle_market(market, arbiter, outcome)
+        }
+
+        // The arbiter voids a disputed market whose game was cancelled. No
+        // outcome stands, and the arbiter keeps both bonds as for a ruling.
+        pub fn arbitrate_void(ctx: Context<ArbitrateOutcome>) -> Result<()> {
+            require!(
+                ctx.accounts.market.resolution_state == ResolutionState::Disputed,
+                ErrorCode::NotDisputed
+            );
+
+            award_bonds_to_arbiter(ctx.accounts, ctx.bumps.market)?;
+
+            let market = &mut ctx.accounts.market;
+            let proposal = &ctx.accounts.proposal;
+            let arbiter = ctx.accounts.arbiter.key();
+            emit_resolution_event(market.key(), proposal, ResolutionAction::Voided, arbiter, proposal.outcome)?;
+
+            market.resolution_state = ResolutionState::Idle;
+            void_settlement(market, arbiter)
+        }
+
+        // Register a resolver committee. Committees are immutable; rotate
+        // members by creating a new one.
+        pub fn create_committee(
+            ctx: Context<CreateCommittee>,
+            name: String,
+            members: Vec<Pubkey>,
+            threshold: u8,
+        ) -> Result<()> {
+            require!(name.len() <= ResolverCommittee::MAX_NAME_LEN, ErrorCode::NameTooLong);
+            require!(
+                members.len() <= ResolverCommittee::MAX_MEMBERS
+                    && (threshold as usize) <= members.len()
+                    && 2 * threshold as usize > members.len(),
+                ErrorCode::InvalidCommittee
+            );
+            for (i, member) in members.iter().enumerate() {
+                require!(!members[..i].contains(member), ErrorCode::InvalidCommittee);
+            }
+
+            let committee = &mut ctx.accounts.committee;
+            committee.name = name;
+            committee.members = members;
+            committee.threshold = threshold;
+            committee.bump = ctx.bumps.committee;
+
+            msg!("Committee {}: {} of {}", committee.name, threshold, committee.members.len());
+
+            Ok(())
+        }
+
+        // Hand a market's resolution to a committee. From then on only its votes,
+        // or the admin if they deadlock, can settle the market.
+        pub fn assign_committee(ctx: Context<AssignCommittee>) -> Result<()> {
+            let market = &mut ctx.accounts.market;
+
+            // Votes pick an outcome, which a scalar market can't settle on
+            require!(!matches!(market.kind, MarketKind::Scalar { .. }), ErrorCode::InvalidMarketKind);
+            require!(!market.is_resolved, ErrorCode::AlreadyResolved);
+            require!(!market.is_voided, ErrorCode::MarketVoided);
+            market.check_resolution_idle()?;
+
+            let ballot = &mut ctx.accounts.ballot;
+            ballot.market = market.key();
+            ballot.committee = ctx.accounts.committee.key();
+            ballot.votes = vec![None; ctx.accounts.committee.members.len()];
+            ballot.bump = ctx.bumps.ballot;
+            market.resolution_state = ResolutionState::Committee;
+
+            msg!("Market {} assigned to committee {}", market.game_id, ctx.accounts.committee.name);
+
+            Ok(())
+        }
+
+        // A committee member's vote. The market settles on the vote that brings
+        // an outcome to the threshold, and turns Contested once no outcome can.
+        pub fn vote_outcome(ctx: Context<VoteOutcome>, outcome: u8) -> Result<()> {
+            let committee = &ctx.accounts.committee;
+            let ballot = &mut ctx.accounts.ballot;
+            let voter = ctx.accounts.voter.key();
+
+            require!(
+                ctx.accounts.market.resolution_state == ResolutionState::Committee,
+                ErrorCode::NotCommitteeVoting
+            );
+            require!(
+                ctx.accounts.market.is_trading_closed(Clock::get()?.unix_timestamp),
+                ErrorCode::TradingNotHalted
+            );
+            ctx.accounts.market.check_outcome(outcome)?;
+
+            let index = committee
+                .members
+                .iter()
+                .position(|m| *m == voter)
+                .ok_or(ErrorCode::NotCommitteeMember)?;
+            require!(ballot.votes[index].is_none(), ErrorCode::AlreadyVoted);
+            ballot.votes[index] = Some(outcome);
+
+            let decision = ballot.decision(committee.threshold);
+            let market = &mut ctx.accounts.market;
+
+            let clock = Clock::get()?;
+            emit!(CommitteeVoteEvent {
+                version: EVENT_VERSION,
+                market: market.key(),
+                committee: committee.key(),
+                voter,
+                outcome,
+                votes: ballot.votes.clone(),
+                contested: decision == CommitteeDecision::Contested,
+                slot: clock.slot,
+                timestamp: clock.unix_timestamp,
+            });
+
+            match decision {
+                CommitteeDecision::Pending => Ok(()),
+                CommitteeDecision::Contested => {
+                    market.resolution_state = ResolutionState::Contested;
+                    msg!("Committee deadlocked on {}", market.game_id);
+                    Ok(())
+                }
+                CommitteeDecision::Decided(winner) => {
+                    market.resolution_state = ResolutionState::Idle;
+                    settle_market(market, committee.key(), winner)
+                }
+            }
+        }
+
+        // The admin settles a market its committee couldn't agree on
+        pub fn resolve_contested(ctx: Context<ResolveContested>, outcome: u8) -> Result<()> {
+            let market = &mut ctx.accounts.market;
+
+            require!(market.resolution_state == ResolutionState::Contested, ErrorCode::NotContested);
+
+            market.resolution_state = ResolutionState::Idle;
+            settle_market(market, ctx.accounts.admin.key(), outcome)
+        }
+
+        // The admin voids a committee market whose game was cancelled, whether
+        // the committee is still voting or has deadlocked
+        pub fn void_committee_market(ctx: Context<ResolveContested>) -> Result<()> {
+            let market = &mut ctx.accounts.market;
+
+            require!(
+                matches!(
+                    market.resolution_state,
+                    ResolutionState::Committee | ResolutionState::Contested
+                ),
+                ErrorCode::NotCommitteeVoting
+            );
+
+            market.resolution_state = ResolutionState::Idle;
+            void_settlement(market, ctx.accounts.admin.key())
+        }
+
+        // Burn winning tokens for a pro-rata share of the pool. On a draw the
+        // caller's tokens on both sides are burned and paid out.
+        pub fn claim_winnings(ctx: Context<Claim>) -> Result<()> {
+            let market = &ctx.accounts.market;
+
+            require!(market.is_resolved, ErrorCode::MarketNotResolved);
+
+            let winner = market.winner.ok_or(ErrorCode::NoWinner)?;
+
+            let balance_a = if winner != 1 { ctx.accounts.user_team_a_account.amount } else { 0 };
+            let balance_b = if winner != 0 { ctx.accounts.user_team_b_account.amount } else { 0 };
+
+            require!(balance_a > 0 || balance_b > 0, ErrorCode::NoWinningTokens);
+
+            let supply_before = if winner == 0 { market.team_a_supply } else { market.team_b_supply };
+
+            let (payout_a, payout_b) = settle_claim(ctx.accounts, ctx.bumps.market, balance_a, balance_b)?;
+            let payout = payout_a + payout_b;
+
+            let market = &ctx.accounts.market;
+            let clock = Clock::get()?;
+            if winner == MarketV2::DRAW {
+                emit!(DrawClaimedEvent {
+                    version: EVENT_VERSION,
+                    market: market.key(),
+                    user: ctx.accounts.user.key(),
+                    team_a_amount: balance_a,
+                    team_b_amount: balance_b,
+                    payout,
+                    team_a_supply: market.team_a_supply,
+                    team_b_supply: market.team_b_supply,
+                    pool_value: market.pool_value,
+                    slot: clock.slot,
+                    timestamp: clock.unix_timestamp,
+                });
+            } else {
+                let token_balance = balance_a + balance_b;
+                emit!(WinningsClaimedEvent {
+                    version: EVENT_VERSION,
+                    market: market.key(),
+                    user: ctx.accounts.user.key(),
+                    team: winner,
+                    token_amount: token_balance,
+                    payout,
+                    price_before: market.curve(winner).spot_price(supply_before)?,
+                    price_after: market.curve(winner).spot_price(supply_before - token_balance)?,
+                    team_a_supply: market.team_a_supply,
+                    team_b_supply: market.team_b_supply,
+                    pool_value: market.pool_value,
+                    slot: clock.slot,
+                    timestamp: clock.unix_timestamp,
+                });
+            }
+
+            msg!("Claimed {} USDC for {} team A and {} team B tokens", payout, balance_a, balance_b);
+
+            Ok(())
+        }
+
+        // Cancel a postponed or abandoned game. Trading stops for good and every
+        // holder can take a refund with claim_refund.
+        pub fn void_market(ctx: Context<VoidMarket>) -> Result<()> {
+            let market = &mut ctx.accounts.market;
+
+            require!(!market.is_resolved, ErrorCode::AlreadyResolved);
+            require!(!market.is_voided, ErrorCode::MarketVoided);
+            market.check_resolution_idle()?;
+
+            void_settlement(market, ctx.accounts.authority.key())
+        }
+
+        // Burn all of the caller's team tokens in a voided market for a refund.
+        //
+        // Refund rule: each side is unwound against the USDC that side put into
+        // the curve (team_x_pool: net buys minus sell proceeds), using the same
+        // pro-rata payout as claim_winnings. LMSR sides share one pool, so
+        // void_market instead values every token at its last price.
+        pub fn claim_refund(ctx: Context<Claim>) -> Result<()> {
+            let market = &ctx.accounts.market;
+
+            require!(market.is_voided, ErrorCode::MarketNotVoided);
+
+            let balance_a = ctx.accounts.user_team_a_account.amount;
+            let balance_b = ctx.accounts.user_team_b_account.amount;
+
+            require!(balance_a > 0 || balance_b > 0, ErrorCode::NoTokensToRefund);
+
+            let (refund_a, refund_b) = settle_claim(ctx.accounts, ctx.bumps.market, balance_a, balance_b)?;
+            let refund = refund_a + refund_b;
+
+            let market = &ctx.accounts.market;
+            let clock = Clock::get()?;
+            emit!(RefundClaimedEvent {
+                version: EVENT_VERSION,
+                market: market.key(),
+                user: ctx.accounts.user.key(),
+                team_a_amount: balance_a,
+                team_b_amount: balance_b,
+                refund,
+                team_a_supply: market.team_a_supply,
+                team_b_supply: market.team_b_supply,
+                pool_value: market.pool_value,
+                slot: clock.slot,
+                timestamp: clock.unix_timestamp,
+            });
+
+            msg!("Refunded {} USDC for {} team A and {} team B tokens", refund, balance_a, balance_b);
+
+            Ok(())
+        }
+
+        // Send the market's accrued protocol fees to a treasury token account
+        pub fn withdraw_protocol_fees(ctx: Context<WithdrawProtocolFees>) -> Result<()> {
+            let market = &mut ctx.accounts.market;
+            let amount = market.protocol_fees_accrued;
+
+            require!(amount > 0, ErrorCode::NoFeesToWithdraw);
+
+            market.protocol_fees_accrued = 0;
+            let seeds = &[
+                b"market",
+                market.game_id.as_bytes(),
+                &[ctx.bumps.market],
+            ];
+            transfer_from_vault(
+                market.to_account_info(),
+                seeds,
+                &ctx.accounts.fee_vault,
+                &ctx.accounts.destination,
+                &ctx.accounts.token_program,
+                amount,
+            )?;
+
+            let clock = Clock::get()?;
+            emit!(FeesWithdrawnEvent {
+                version: EVENT_VERSION,
+                market: market.key(),
+                kind: FeeKind::Protocol,
+                destination: ctx.accounts.destination.key(),
+                amount,
+                slot: clock.slot,
+                timestamp: clock.unix_timestamp,
+            });
+
+            msg!("Withdrew {} USDC of protocol fees", amount);
+
+            Ok(())
+        }
+
+        // Send the market's accrued creator fees to the market authority
+        pub fn withdraw_creator_fees(ctx: Context<WithdrawCreatorFees>) -> Result<()> {
+            let market = &mut ctx.accounts.market;
+            let amount = market.creator_fees_accrued;
+
+            require!(amount > 0, ErrorCode::NoFeesToWithdraw);
+
+            market.creator_fees_accrued = 0;
+            let seeds = &[
+                b"market",
+                market.game_id.as_bytes(),
+                &[ctx.bumps.market],
+            ];
+            transfer_from_vault(
+                market.to_account_info(),
+                seeds,
+                &ctx.accounts.fee_vault,
+                &ctx.accounts.destination,
+                &ctx.accounts.token_program,
+                amount,
+            )?;
+
+            let clock = Clock::get()?;
+            emit!(FeesWithdrawnEvent {
+                version: EVENT_VERSION,
+                market: market.key(),
+                kind: FeeKind::Creator,
+                destination: ctx.accounts.destination.key(),
+                amount,
+                slot: clock.slot,
+                timestamp: clock.unix_timestamp,
+            });
+
+            msg!("Withdrew {} USDC of creator fees", amount);
+
+            Ok(())
+        }
+
+        // Return what is left of an LMSR creator's subsidy once the market has
+        // been resolved or voided and every holder's share is set aside
+        pub fn withdraw_subsidy(ctx: Context<WithdrawSubsidy>) -> Result<()> {
+            let market = &mut ctx.accounts.market;
+            let amount = market.subsidy_surplus;
+
+            require!(amount > 0, ErrorCode::NoSubsidyToWithdraw);
+
+            market.subsidy_surplus = 0;
+            let seeds = &[
+                b"market",
+                market.game_id.as_bytes(),
+                &[ctx.bumps.market],
+            ];
+            transfer_from_vault(
+                market.to_account_info(),
+                seeds,
+                &ctx.accounts.usdc_vault,
+                &ctx.accounts.destination,
+                &ctx.accounts.token_program,
+                amount,
+            )?;
+
+            let clock = Clock::get()?;
+            emit!(SubsidyWithdrawnEvent {
+                version: EVENT_VERSION,
+                market: market.key(),
+                destination: ctx.accounts.destination.key(),
+                subsidy: market.subsidy,
+                amount,
+                slot: clock.slot,
+                timestamp: clock.unix_timestamp,
+            });
+
+            msg!("Withdrew {} of {} USDC subsidy", amount, market.subsidy);
+
+            Ok(())
+        }
+        // Multi-outcome markets
+        //
+        // One market with up to MultiMarket::MAX_OUTCOMES outcomes (golf winner,
+        // MVP, division winner). Every outcome has its own mint and its own
+        // linear curve; all outcomes share one collateral vault.
+
+        pub fn create_multi_market(
+            ctx: Context<CreateMultiMarket>,
+            game_id: String,
+            base_price: u64,
+            slope: u64,
+        ) -> Result<()> {
+            CurveType::Linear.validate(base_price, slope)?;
+
+            let market = &mut ctx.accounts.market;
+            market.authority = ctx.accounts.authority.key();
+            market.game_id = game_id;
+            market.usdc_vault = ctx.accounts.usdc_vault.key();
+            market.usdc_mint = ctx.accounts.usdc_mint.key();
+            market.usdc_decimals = ctx.accounts.usdc_mint.decimals;
+            market.fee_vault = ctx.accounts.fee_vault.key();
+            market.protocol_fee_bps = ctx.accounts.config.protocol_fee_bps;
+            market.creator_fee_bps = ctx.accounts.config.creator_fee_bps;
+            market.protocol_fees_accrued = 0;
+            market.creator_fees_accrued = 0;
+            market.base_price = base_price;
+            market.slope = slope;
+            market.pool_value = 0;
+            market.outcomes = Vec::new();
+            market.trading_halted = false;
+            market.is_resolved = false;
+            market.winner = None;
+
+            let clock = Clock::get()?;
+            emit!(MultiMarketCreatedEvent {
+                version: EVENT_VERSION,
+                market: market.key(),
+                authority: market.authority,
+                game_id: market.game_id.clone(),
+                usdc_mint: market.usdc_mint,
+                base_price,
+                slope,
+                protocol_fee_bps: market.protocol_fee_bps,
+                creator_fee_bps: market.creator_fee_bps,
+                slot: clock.slot,
+                timestamp: clock.unix_timestamp,
+            });
+
+            msg!("Multi-outcome market created: {}", market.game_id);
+
+            Ok(())
+        }
+
+        // Append an outcome and create its mint. Outcomes are fixed once the
+        // first trade lands.
+        pub fn add_outcome(ctx: Context<AddOutcome>, name: String) -> Result<()> {
+            let market = &mut ctx.accounts.market;
+
+            require!(market.pool_value == 0 && !market.trading_halted, ErrorCode::OutcomesLocked);
+            require!(market.outcomes.len() < MultiMarket::MAX_OUTCOMES, ErrorCode::TooManyOutcomes);
+            require!(name.len() <= Outcome::MAX_NAME_LEN, ErrorCode::NameTooLong);
+
+            let index = market.outcomes.len() as u8;
+            market.outcomes.push(Outcome {
+                name,
+                mint: ctx.accounts.outcome_mint.key(),
+                supply: 0,
+                pool: 0,
+            });
+
+            let clock = Clock::get()?;
+            emit!(OutcomeAddedEvent {
+                version: EVENT_VERSION,
+                market: market.key(),
+                outcome: index,
+                name: market.outcomes[index as usize].name.clone(),
+                mint: ctx.accounts.outcome_mint.key(),
+                price: market.curve().spot_price(0)?,
+                slot: clock.slot,
+                timestamp: clock.unix_timestamp,
+            });
+
+            msg!("Outcome {} added: {}", index, market.outcomes[index as usize].name);
+
+            Ok(())
+        }
+
+        pub fn buy_outcome(
+            ctx: Context<BuyOutcome>,
+            outcome: u8,
+            usdc_amount: u64,
+            min_tokens_out: u64,
+            deadline_unix_ts: Option<i64>,
+        ) -> Result<()> {
+            check_deadline(deadline_unix_ts)?;
+
+            let market = &mut ctx.accounts.market;
+
+            require!(!market.trading_halted, ErrorCode::TradingHalted);
+            require!(market.outcomes.len() >= MultiMarket::MIN_OUTCOMES, ErrorCode::TooFewOutcomes);
+            require!((outcome as usize) < market.outcomes.len(), ErrorCode::InvalidOutcome);
+
+            let current_supply = market.outcomes[outcome as usize].supply;
+
+            // Fees come off the top; only the remainder goes into the curve
+            let (protocol_fee, creator_fee) = calculate_fees(
+                usdc_amount,
+                market.protocol_fee_bps,
+                market.creator_fee_bps,
+            )?;
+            let net_amount = usdc_amount - protocol_fee - creator_fee;
+
+            let curve = market.curve();
+            let tokens_out = curve.tokens_for_usdc(current_supply, net_amount)?;
+
+            require!(tokens_out >= min_tokens_out, ErrorCode::SlippageExceeded);
+
+            let price_before = curve.spot_price(current_supply)?;
+
+            // Transfer USDC
+            let cpi_ctx = CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.buyer_usdc.to_account_info(),
+                    to: ctx.accounts.usdc_vault.to_account_info(),
+                    authority: ctx.accounts.buyer.to_account_info(),
+                },
+            );
+            token::transfer(cpi_ctx, net_amount)?;
+
+            let total_fee = protocol_fee + creator_fee;
+            if total_fee > 0 {
+                let cpi_ctx = CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.buyer_usdc.to_account_info(),
+                        to: ctx.accounts.fee_vault.to_account_info(),
+                        authority: ctx.accounts.buyer.to_account_info(),
+                    },
+                );
+                token::transfer(cpi_ctx, total_fee)?;
+            }
+
+            // Mint outcome tokens
+            let seeds = &[
+                b"multi_market",
+                market.game_id.as_bytes(),
+                &[ctx.bumps.market],
+            ];
+            let signer = &[&seeds[..]];
+
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.outcome_mint.to_account_info(),
+                    to: ctx.accounts.buyer_outcome_account.to_account_info(),
+                    authority: market.to_account_info(),
+                },
+                signer,
+            );
+            token::mint_to(cpi_ctx, tokens_out)?;
+
+            // Update state
+            let entry = &mut market.outcomes[outcome as usize];
+            entry.supply += tokens_out;
+            entry.pool += net_amount;
+            market.pool_value += net_amount;
+            market.protocol_fees_accrued += protocol_fee;
+            market.creator_fees_accrued += creator_fee;
+
+            let clock = Clock::get()?;
+            emit!(OutcomeTradeEvent {
+                version: EVENT_VERSION,
+                market: market.key(),
+                trader: ctx.accounts.buyer.key(),
+                action: TradeAction::Buy,
+                outcome,
+                usdc_amount,
+                token_amount: tokens_out,
+                protocol_fee,
+                creator_fee,
+                price_before,
+                price_after: curve.spot_price(current_supply + tokens_out)?,
+                outcome_supply: current_supply + tokens_out,
+                pool_value: market.pool_value,
+                slot: clock.slot,
+                timestamp: clock.unix_timestamp,
+            });
+
+            msg!("Bought {} outcome {} tokens for {} USDC", tokens_out, outcome, usdc_amount);
+
+            Ok(())
+        }
+
+        pub fn sell_outcome(
+            ctx: Context<SellOutcome>,
+            outcome: u8,
+            token_amount: u64,
+            min_usdc_out: u64,
+            deadline_unix_ts: Option<i64>,
+        ) -> Result<()> {
+            check_deadline(deadline_unix_ts)?;
+
+            let market = &mut ctx.accounts.market;
+
+            require!(!market.trading_halted, ErrorCode::TradingHalted);
+            require!((outcome as usize) < market.outcomes.len(), ErrorCode::InvalidOutcome);
+
+            let current_supply = market.outcomes[outcome as usize].supply;
+
+            require!(token_amount <= current_supply, ErrorCode::InsufficientSupply);
+
+            let curve = market.curve();
+            let usdc_out = curve.sell_proceeds(current_supply, token_amount)?;
+
+            require!(usdc_out <= market.outcomes[outcome as usize].pool, ErrorCode::InsufficientPoolBalance);
+
+            // Fees come out of the curve proceeds before they reach the seller
+            let (protocol_fee, creator_fee) = calculate_fees(
+                usdc_out,
+                market.protocol_fee_bps,
+                market.creator_fee_bps,
+            )?;
+            let total_fee = protocol_fee + creator_fee;
+            let net_out = usdc_out - total_fee;
+
+            require!(net_out >= min_usdc_out, ErrorCode::SlippageExceeded);
+
+            let price_before = curve.spot_price(current_supply)?;
+
+            // Burn outcome tokens from seller
+            let cpi_ctx = CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.outcome_mint.to_account_info(),
+                    from: ctx.accounts.seller_outcome_account.to_account_info(),
+                    authority: ctx.accounts.seller.to_account_info(),
+                },
+            );
+            token::burn(cpi_ctx, token_amount)?;
+
+            // Transfer USDC back to seller
+            let seeds = &[
+                b"multi_market",
+                market.game_id.as_bytes(),
+                &[ctx.bumps.market],
+            ];
+            let signer = &[&seeds[..]];
+
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.usdc_vault.to_account_info(),
+                    to: ctx.accounts.seller_usdc.to_account_info(),
+                    authority: market.to_account_info(),
+                },
+                signer,
+            );
+            token::transfer(cpi_ctx, net_out)?;
+
+            if total_fee > 0 {
+                let cpi_ctx = CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.usdc_vault.to_account_info(),
+                        to: ctx.accounts.fee_vault.to_account_info(),
+                        authority: market.to_account_info(),
+                    },
+                    signer,
+                );
+                token::transfer(cpi_ctx, total_fee)?;
+            }
+
+            // Update state
+            let entry = &mut market.outcomes[outcome as usize];
+            entry.supply -= token_amount;
+            entry.pool -= usdc_out;
+            market.pool_value -= usdc_out;
+            market.protocol_fees_accrued += protocol_fee;
+            market.creator_fees_accrued += creator_fee;
+
+            let clock = Clock::get()?;
+            emit!(OutcomeTradeEvent {
+                version: EVENT_VERSION,
+                market: market.key(),
+                trader: ctx.accounts.seller.key(),
+                action: TradeAction::Sell,
+                outcome,
+                usdc_amount: net_out,
+                token_amount,
+                protocol_fee,
+                creator_fee,
+                price_before,
+                price_after: curve.spot_price(current_supply - token_amount)?,
+                outcome_supply: current_supply - token_amount,
+                pool_value: market.pool_value,
+                slot: clock.slot,
+                timestamp: clock.unix_timestamp,
+            });
+
+            msg!("Sold {} outcome {} tokens for {} USDC", token_amount, outcome, net_out);
+
+            Ok(())
+        }
+
+        pub fn halt_multi_market(ctx: Context<HaltMultiMarket>) -> Result<()> {
+            let market = &mut ctx.accounts.market;
+
+            require!(!market.trading_halted, ErrorCode::AlreadyHalted);
+
+            market.trading_halted = true;
+
+            let clock = Clock::get()?;
+            emit!(MultiMarketHaltedEvent {
+                version: EVENT_VERSION,
+                market: market.key(),
+                authority: ctx.accounts.authority.key(),
+                outcome_supplies: market.outcomes.iter().map(|o| o.supply).collect(),
+                pool_value: market.pool_value,
+                slot: clock.slot,
+                timestamp: clock.unix_timestamp,
+            });
+
+            msg!("Trading halted for market: {}", market.game_id);
+
+            Ok(())
+        }
+
+        pub fn resolve_multi_market(ctx: Context<ResolveMultiMarket>, winner: u8) -> Result<()> {
+            let market = &mut ctx.accounts.market;
+
+            require!(market.trading_halted, ErrorCode::TradingNotHalted);
+            require!(!market.is_resolved, ErrorCode::AlreadyResolved);
+            require!((winner as usize) < market.outcomes.len(), ErrorCode::InvalidOutcome);
+            // Nobody could claim an unheld winner, stranding the pool in the vault
+            require!(market.outcomes[winner as usize].supply > 0, ErrorCode::WinnerHasNoHolders);
+
+            market.winner = Some(winner);
+            market.is_resolved = true;
+
+            let clock = Clock::get()?;
+            emit!(MultiMarketResolvedEvent {
+                version: EVENT_VERSION,
+                market: market.key(),
+                resolver: ctx.accounts.resolver.key(),
+                winner,
+                winner_supply: market.outcomes[winner as usize].supply,
+                pool_value: market.pool_value,
+                slot: clock.slot,
+                timestamp: clock.unix_timestamp,
+            });
+
+            msg!("Market resolved. Winner: {}", market.outcomes[winner as usize].name);
+
+            Ok(())
+        }
+
+        // Burn winning outcome tokens for a pro-rata share of the whole pool
+        pub fn claim_multi_winnings(ctx: Context<ClaimMultiWinnings>) -> Result<()> {
+            let market = &mut ctx.accounts.market;
+
+            require!(market.is_resolved, ErrorCode::MarketNotResolved);
+
+            let winner = market.winner.ok_or(ErrorCode::NoWinner)? as usize;
+            let token_balance = ctx.accounts.user_outcome_account.amount;
+
+            require!(token_balance > 0, ErrorCode::NoWinningTokens);
+
+            let total_supply = market.outcomes[winner].supply;
+            let payout = pro_rata_share(token_balance, market.pool_value, total_supply)?;
+
+            let cpi_ctx = CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.outcome_mint.to_account_info(),
+                    from: ctx.accounts.user_outcome_account.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            );
+            token::burn(cpi_ctx, token_balance)?;
+
+            let seeds = &[
+                b"multi_market",
+                market.game_id.as_bytes(),
+                &[ctx.bumps.market],
+            ];
+            let signer = &[&seeds[..]];
+
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.usdc_vault.to_account_info(),
+                    to: ctx.accounts.user_usdc.to_account_info(),
+                    authority: market.to_account_info(),
+                },
+                signer,
+            );
+            token::transfer(cpi_ctx, payout)?;
+
+            // Update state
+            market.outcomes[winner].supply -= token_balance;
+            market.pool_value -= payout;
+
+            let clock = Clock::get()?;
+            emit!(OutcomeClaimedEvent {
+                version: EVENT_VERSION,
+                market: market.key(),
+                user: ctx.accounts.user.key(),
+                outcome: winner as u8,
+                token_amount: token_balance,
+                payout,
+                outcome_supply: market.outcomes[winner].supply,
+                pool_value: market.pool_value,
+                slot: clock.slot,
+                timestamp: clock.unix_timestamp,
+            });
+
+            msg!("Claimed {} USDC for {} winning tokens", payout, token_balance);
+
+            Ok(())
+        }
+
+        pub fn withdraw_multi_protocol_fees(ctx: Context<WithdrawMultiProtocolFees>) -> Result<()> {
+            let market = &mut ctx.accounts.market;
+            let amount = market.protocol_fees_accrued;
+
+            require!(amount > 0, ErrorCode::NoFeesToWithdraw);
+
+            market.protocol_fees_accrued = 0;
+            let seeds = &[
+                b"multi_market",
+                market.game_id.as_bytes(),
+                &[ctx.bumps.market],
+            ];
+            transfer_from_vault(
+                market.to_account_info(),
+                seeds,
+                &ctx.accounts.fee_vault,
+                &ctx.accounts.destination,
+                &ctx.accounts.token_program,
+                amount,
+            )?;
+
+            let clock = Clock::get()?;
+            emit!(FeesWithdrawnEvent {
+                version: EVENT_VERSION,
+                market: market.key(),
+                kind: FeeKind::Protocol,
+                destination: ctx.accounts.destination.key(),
+                amount,
+                slot: clock.slot,
+                timestamp: clock.unix_timestamp,
+            });
+
+            msg!("Withdrew {} USDC of protocol fees", amount);
+
+            Ok(())
+        }
+
+        pub fn withdraw_multi_creator_fees(ctx: Context<WithdrawMultiCreatorFees>) -> Result<()> {
+            let market = &mut ctx.accounts.market;
+            let amount = market.creator_fees_accrued;
+
+            require!(amount > 0, ErrorCode::NoFeesToWithdraw);
+
+            market.creator_fees_accrued = 0;
+            let seeds = &[
+                b"multi_market",
+                market.game_id.as_bytes(),
+                &[ctx.bumps.market],
+            ];
+            transfer_from_vault(
+                market.to_account_info(),
+                seeds,
+                &ctx.accounts.fee_vault,
+                &ctx.accounts.destination,
+                &ctx.accounts.token_program,
+                amount,
+            )?;
+
+            let clock = Clock::get()?;
+            emit!(FeesWithdrawnEvent {
+                version: EVENT_VERSION,
+                market: market.key(),
+                kind: FeeKind::Creator,
+                destination: ctx.accounts.destination.key(),
+                amount,
+                slot: clock.slot,
+                timestamp: clock.unix_timestamp,
+            });
+
+            msg!("Withdrew {} USDC of creator fees", amount);
+
+            Ok(())
+        }
+    }
+}
+
+pub use instructions::*;
+
+// `market_seeds` are the signer seeds of the market PDA that owns the vault
+fn transfer_from_vault<'info>(
+    market: AccountInfo<'info>,
+    market_seeds: &[&[u8]],
+    vault: &Account<'info, TokenAccount>,
+    destination: &Account<'info, TokenAccount>,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+) -> Result<()> {
+    let signer = &[market_seeds];
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        Transfer {
+            from: vault.to_account_info(),
+            to: destination.to_account_info(),
+            authority: market,
+        },
+        signer,
+    );
+    token::transfer(cpi_ctx, amount)
+}
+
+// Trades take an optional deadline so a transaction that lands late (say,
+// after news breaks) fails instead of filling at a stale price
+fn check_deadline(deadline_unix_ts: Option<i64>) -> Result<()> {
+    if let Some(deadline) = deadline_unix_ts {
+        require!(Clock::get()?.unix_timestamp <= deadline, ErrorCode::DeadlineExceeded);
+    }
+    Ok(())
+}
+
+// Mint a quoted buy: takes quote.amount USDC from the buyer, fees to the fee
+// vault and the rest into the pool, and mints quote.tokens_out
+fn execute_buy(accounts: &mut BuyOnCurve, market_bump: u8, quote: &TradeQuote) -> Result<()> {
+    let market = &mut accounts.market;
+    let (team, usdc_amount, tokens_out) = (quote.team, quote.amount, quote.tokens_out);
+
+    // Fees come off the top; only the remainder goes into the curve
+    let (protocol_fee, creator_fee) = (quote.protocol_fee, quote.creator_fee);
+    let net_amount = usdc_amount - quote.fee;
+    
+    // Transfer USDC
+    let cpi_ctx = CpiContext::new(
+        accounts.token_program.to_account_info(),
+        Transfer {
+            from: accounts.buyer_usdc.to_account_info(),
+            to: accounts.usdc_vault.to_account_info(),
+            authority: accounts.buyer.to_account_info(),
+        },
+    );
+    token::transfer(cpi_ctx, net_amount)?;
+    
+    let total_fee = protocol_fee + creator_fee;
+    if total_fee > 0 {
+        let cpi_ctx = CpiContext::new(
+            accounts.token_program.to_account_info(),
+            Transfer {
+                from: accounts.buyer_usdc.to_account_info(),
+                to: accounts.fee_vault.to_account_info(),
+                authority: accounts.buyer.to_account_info(),
+            },
+        );
+        token::transfer(cpi_ctx, total_fee)?;
+    }
+    
+    // Mint tokens
+    let mint = if team == 0 {
+        &accounts.team_a_mint
+    } else {
+        &accounts.team_b_mint
+    };
+    
+    let token_account = if team == 0 {
+        &accounts.buyer_team_a_account
+    } else {
+        &accounts.buyer_team_b_account
+    };
+    
+    let seeds = &[
+        b"market",
+        market.game_id.as_bytes(),
+        &[market_bump],
+    ];
+    let signer = &[&seeds[..]];
+    
+    let cpi_ctx = CpiContext::new_with_signer(
+        accounts.token_program.to_account_info(),
+        MintTo {
+            mint: mint.to_account_info(),
+            to: token_account.to_account_info(),
+            authority: market.to_account_info(),
+        },
+        signer,
+    );
+    token::mint_to(cpi_ctx, tokens_out)?;
+    
+    // Update state
+    if team == 0 {
+        market.team_a_supply += tokens_out;
+    } else {
+        market.team_b_supply += tokens_out;
+    }
+    market.add_to_side_pool(team, net_amount);
+    market.pool_value += net_amount;
+    market.protocol_fees_accrued += protocol_fee;
+    market.creator_fees_accrued += creator_fee;
+    
+    let clock = Clock::get()?;
+    if let Some(position) = accounts.position.as_mut() {
+        position.record_buy(team, usdc_amount, tokens_out, clock.slot)?;
+    }
+
+    emit!(TradeEvent {
+        version: EVENT_VERSION,
+        market: market.key(),
+        trader: accounts.buyer.key(),
+        action: TradeAction::Buy,
+        team,
+        usdc_amount,
+        token_amount: tokens_out,
+        protocol_fee,
+        creator_fee,
+        price_before: quote.price_before,
+        price_after: quote.price_after,
+        team_a_supply: market.team_a_supply,
+        team_b_supply: market.team_b_supply,
+        pool_value: market.pool_value,
+        slot: clock.slot,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!("Bought {} tokens for {} USDC", tokens_out, usdc_amount);
+    
+    Ok(())
+}
+
+// Fill a quoted sell: burns quote.amount tokens and pays quote.usdc_out to
+// the seller and the fees to the fee vault, both out of the pool
+fn execute_sell(accounts: &mut SellOnCurve, market_bump: u8, quote: &TradeQuote) -> Result<()> {
+    let market = &mut accounts.market;
+    let (team, token_amount, net_out) = (quote.team, quote.amount, quote.usdc_out);
+
+    // Fees come out of the curve proceeds before they reach the seller
+    let (protocol_fee, creator_fee) = (quote.protocol_fee, quote.creator_fee);
+    let total_fee = quote.fee;
+    let usdc_out = net_out + total_fee;
+    
+    // Burn tokens from seller
+    let mint = if team == 0 {
+        &accounts.team_a_mint
+    } else {
+        &accounts.team_b_mint
+    };
+    
+    let token_account = if team == 0 {
+        &accounts.seller_team_a_account
+    } else {
+        &accounts.seller_team_b_account
+    };
+    
+    let cpi_ctx = CpiContext::new(
+        accounts.token_program.to_account_info(),
+        Burn {
+            mint: mint.to_account_info(),
+            from: token_account.to_account_info(),
+            authority: accounts.seller.to_account_info(),
+        },
+    );
+    token::burn(cpi_ctx, token_amount)?;
+    
+    // Transfer USDC back to seller
+    let seeds = &[
+        b"market",
+        market.game_id.as_bytes(),
+        &[market_bump],
+    ];
+    let signer = &[&seeds[..]];
+    
+    let cpi_ctx = CpiContext::new_with_signer(
+        accounts.token_program.to_account_info(),
+        Transfer {
+            from: accounts.usdc_vault.to_account_info(),
+            to: accounts.seller_usdc.to_account_info(),
+            authority: market.to_account_info(),
+        },
+        signer,
+    );
+    token::transfer(cpi_ctx, net_out)?;
+    
+    if total_fee > 0 {
+        let cpi_ctx = CpiContext::new_with_signer(
+            accounts.token_program.to_account_info(),
+            Transfer {
+                from: accounts.usdc_vault.to_account_info(),
+                to: accounts.fee_vault.to_account_info(),
+                authority: market.to_account_info(),
+            },
+            signer,
+        );
+        token::transfer(cpi_ctx, total_fee)?;
+    }
+    
+    // Update state
+    if team == 0 {
+        market.team_a_supply -= token_amount;
+    } else {
+        market.team_b_supply -= token_amount;
+    }
+    market.take_from_side_pool(team, usdc_out);
+    market.pool_value -= usdc_out;
+    market.protocol_fees_accrued += protocol_fee;
+    market.creator_fees_accrued += creator_fee;
+    
+    let clock = Clock::get()?;
+    if let Some(position) = accounts.position.as_mut() {
+        position.record_sell(team, token_amount, net_out, clock.slot)?;
+    }
+
+    emit!(TradeEvent {
+        version: EVENT_VERSION,
+        market: market.key(),
+        trader: accounts.seller.key(),
+        action: TradeAction::Sell,
+        team,
+        usdc_amount: net_out,
+        token_amount,
+        protocol_fee,
+        creator_fee,
+        price_before: quote.price_before,
+        price_after: quote.price_after,
+        team_a_supply: market.team_a_supply,
+        team_b_supply: market.team_b_supply,
+        pool_value: market.pool_value,
+        slot: clock.slot,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!("Sold {} tokens for {} USDC", token_amount, net_out);
+    
+    Ok(())
+}
+
+// Settle a market on `winner`, shared by every resolution path. `resolver`
+// is whoever decided the outcome and is recorded in the event.
+fn settle_market(market: &mut Account<MarketV2>, resolver: Pubkey, winner: u8) -> Result<()> {
+    check_settleable(market)?;
+    market.check_outcome(winner)?;
+
+    settle_pools(market, resolver, winner, MarketV2::winner_share(winner))
+}
+
+// Whether a market may be settled or pushed now
+fn check_settleable(market: &MarketV2) -> Result<()> {
+    // Kickoff closes trading on its own; no halt_trading call is needed
+    let now = Clock::get()?.unix_timestamp;
+    require!(market.is_trading_closed(now), ErrorCode::TradingNotHalted);
+    require!(!market.is_resolved, ErrorCode::AlreadyResolved);
+    require!(!market.is_voided, ErrorCode::MarketVoided);
+    market.check_resolution_idle()
+}
+
+// Split the pool so team A holders are owed `share` (numerator, denominator)
+// of it and team B holders the rest, and record `winner`. If a bonding-curve
+// side that takes the whole pool has no holders, nobody could claim it, so
+// the market is voided for refunds instead. LMSR needs no such case: the
+// creator is the counterparty and keeps what the winners aren't owed.
+fn settle_pools(market: &mut Account<MarketV2>, resolver: Pubkey, winner: u8, share: (u64, u64)) -> Result<()> {
+    let (num, den) = share;
+    let unclaimable = (num == den && market.team_a_supply == 0) || (num == 0 && market.team_b_supply == 0);
+    if unclaimable && !market.is_lmsr() {
+        msg!("Nobody holds the winning side");
+        return void_settlement(market, resolver);
+    }
+
+    // From here on each side's pool is what its holders are owed
+    market.fold_complete_sets()?;
+    if market.is_lmsr() {
+        market.settle_lmsr(Some(share))?;
+    } else {
+        let (team_a_pool, team_b_pool) = settlement_pools(
+            share,
+            market.pool_value,
+            market.team_a_supply,
+            market.team_b_supply,
+        );
+        market.team_a_pool = team_a_pool;
+        market.team_b_pool = team_b_pool;
+    }
+    market.winner = Some(winner);
+    market.is_resolved = true;
+
+    let clock = Clock::get()?;
+    emit!(MarketResolvedEvent {
+        version: EVENT_VERSION,
+        market: market.key(),
+        resolver,
+        winner,
+        scalar_value: market.scalar_value,
+        team_a_price: market.curve(0).spot_price(market.team_a_supply)?,
+        team_b_price: market.curve(1).spot_price(market.team_b_supply)?,
+        team_a_supply: market.team_a_supply,
+        team_b_supply: market.team_b_supply,
+        pool_value: market.pool_value,
+        slot: clock.slot,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Market resolved. Winner: {}",
+        match winner {
+            0 => &market.team_a,
+            1 => &market.team_b,
+            _ => "draw",
+        }
+    );
+
+    Ok(())
+}
+
+fn emit_resolution_event(
+    market: Pubkey,
+    proposal: &ResolutionProposal,
+    action: ResolutionAction,
+    actor: Pubkey,
+    outcome: u8,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    emit!(ResolutionEvent {
+        version: EVENT_VERSION,
+        market,
+        action,
+        actor,
+        proposer: proposal.proposer,
+        outcome,
+        bond: proposal.bond,
+        dispute_deadline: proposal.dispute_deadline,
+        slot: clock.slot,
+        timestamp: clock.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Pay everything in a disputed market's bond vault to the arbiter
+fn award_bonds_to_arbiter(accounts: &ArbitrateOutcome, market_bump: u8) -> Result<()> {
+    let market = &accounts.market;
+    let seeds = &[
+        b"market",
+        market.game_id.as_bytes(),
+        &[market_bump],
+    ];
+    transfer_from_vault(
+        market.to_account_info(),
+        seeds,
+        &accounts.bond_vault,
+        &accounts.arbiter_usdc,
+        &accounts.token_program,
+        accounts.bond_vault.amount,
+    )
+}
+
+fn emit_oracle_result(result: &Account<OracleResult>) -> Result<()> {
+    let clock = Clock::get()?;
+    emit!(OracleResultPublishedEvent {
+        version: EVENT_VERSION,
+        oracle_result: result.key(),
+        publisher: result.publisher,
+        game_id: result.game_id.clone(),
+        home_score: result.home_score,
+        away_score: result.away_score,
+        status: result.status,
+        slot: clock.slot,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Oracle result for {}: {}-{}", result.game_id, result.home_score, result.away_score);
+
+    Ok(())
+}
+
+// Burn `balance_a` / `balance_b` of the user's team tokens and pay each side's
+// pro-rata share of that side's pool. Shared by claim_winnings and
+// claim_refund; returns the (team A, team B) payouts.
+fn settle_claim(accounts: &mut Claim, market_bump: u8, balance_a: u64, balance_b: u64) -> Result<(u64, u64)> {
+    let market = &mut accounts.market;
+
+    let payout_a = pro_rata_share(balance_a, market.team_a_pool, market.team_a_supply)?;
+    let payout_b = pro_rata_share(balance_b, market.team_b_pool, market.team_b_supply)?;
+
+    for (mint, token_account, amount) in [
+        (&accounts.team_a_mint, &accounts.user_team_a_account, balance_a),
+        (&accounts.team_b_mint, &accounts.user_team_b_account, balance_b),
+    ] {
+        if amount == 0 {
+            continue;
+        }
+        let cpi_ctx = CpiContext::new(
+            accounts.token_program.to_account_info(),
+            Burn {
+                mint: mint.to_account_info(),
+                from: token_account.to_account_info(),
+                authority: accounts.user.to_account_info(),
+            },
+        );
+        token::burn(cpi_ctx, amount)?;
+    }
+
+    let seeds = &[
+        b"market",
+        market.game_id.as_bytes(),
+        &[market_bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        accounts.token_program.to_account_info(),
+        Transfer {
+            from: accounts.usdc_vault.to_account_info(),
+            to: accounts.user_usdc.to_account_info(),
+            authority: market.to_account_info(),
+        },
+        signer,
+    );
+    token::transfer(cpi_ctx, payout_a + payout_b)?;
+
+    market.team_a_supply -= balance_a;
+    market.team_b_supply -= balance_b;
+    market.team_a_pool -= payout_a;
+    market.team_b_pool -= payout_b;
+    market.pool_value -= payout_a + payout_b;
+
+    Ok((payout_a, payout_b))
+}
+
+// Void a market so every holder can reclaim their share of the pool through
+// claim_refund. `authority` is whoever voided it and is recorded in the event.
+fn void_settlement(market: &mut Account<MarketV2>, authority: Pubkey) -> Result<()> {
+    market.is_voided = true;
+    market.trading_halted = true;
+    market.fold_complete_sets()?;
+
+    // LMSR holders are refunded at the last traded prices
+    if market.is_lmsr() {
+        market.settle_lmsr(None)?;
+    }
+
+    let clock = Clock::get()?;
+    emit!(MarketVoidedEvent {
+        version: EVENT_VERSION,
+        market: market.key(),
+        authority,
+        team_a_supply: market.team_a_supply,
+        team_b_supply: market.team_b_supply,
+        team_a_pool: market.team_a_pool,
+        team_b_pool: market.team_b_pool,
+        slot: clock.slot,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Market voided: {}", market.game_id);
+
+    Ok(())
+}
+
+// Settle a market from a final score, home being team A. A push refunds
+// holders as a void would, since neither side of the line won.
+fn settle_scores(
+    market: &mut Account<MarketV2>,
+    resolver: Pubkey,
+    home_score: u16,
+    away_score: u16,
+) -> Result<()> {
+    match market.kind.outcome(home_score, away_score)? {
+        Some(winner) => settle_market(market, resolver, winner),
+        None => {
+            check_settleable(market)?;
+
+            msg!("Push at {}-{}", home_score, away_score);
+            void_settlement(market, resolver)
+        }
+    }
+}
+
+// How pool_value is divided between the two sides at resolution, team A
+// being owed `share` (numerator, denominator) of it. An outright winner takes
+// everything (settle_pools voids instead when it has no holders). A split (a draw's 1/2 or a scalar value inside its range) rounds
+// team A down, leaving the odd unit to team B; if one side has no holders the
+// other side takes the whole pool so no USDC is stranded.
+fn settlement_pools((num, den): (u64, u64), pool_value: u64, team_a_supply: u64, team_b_supply: u64) -> (u64, u64) {
+    match num {
+        _ if num == den => (pool_value, 0),
+        0 => (0, pool_value),
+        _ if team_a_supply == 0 => (0, pool_value),
+        _ if team_b_supply == 0 => (pool_value, 0),
+        _ => {
+            let team_a_pool = (pool_value as u128 * num as u128 / den as u128) as u64;
+            (team_a_pool, pool_value - team_a_pool)
+        }
+    }
+}
+
+fn emit_config_updated(config: &ProtocolConfig) -> Result<()> {
+    let clock = Clock::get()?;
+    emit!(ConfigUpdatedEvent {
+        version: EVENT_VERSION,
+        admin: config.admin,
+        market_creator: config.market_creator,
+        resolver: config.resolver,
+        pauser: config.pauser,
+        treasury: config.treasury,
+        arbiter: config.arbiter,
+        resolution_bond: config.resolution_bond,
+        dispute_window_secs: config.dispute_window_secs,
+        paused: config.paused,
+        protocol_fee_bps: config.protocol_fee_bps,
+        creator_fee_bps: config.creator_fee_bps,
+        allowed_collateral_mints: config.allowed_collateral_mints.clone(),
+        slot: clock.slot,
+        timestamp: clock.unix_timestamp,
+    });
+    Ok(())
+}
+
+// balance / supply of `pool`, rounded down. Used for both winnings and
+// refunds: callers shrink pool and supply together after each payout, so the
+// rate holds for every later holder and the payouts can never exceed `pool`.
+fn pro_rata_share(balance: u64, pool: u64, supply: u64) -> Result<u64> {
+    if balance == 0 {
+        return Ok(0);
+    }
+
+    let share = (balance as u128)
+        .checked_mul(pool as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_div(supply as u128)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    Ok(share as u64)
+}
+
+const BPS_DENOMINATOR: u128 = 10_000;
+
+// Split of `amount` owed to the protocol and the market creator (rounded down)
+fn calculate_fees(amount: u64, protocol_fee_bps: u16, creator_fee_bps: u16) -> Result<(u64, u64)> {
+    let fee = |bps: u16| -> Result<u64> {
+        Ok(((amount as u128)
+            .checked_mul(bps as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            / BPS_DENOMINATOR) as u64)
+    };
+
+    Ok((fee(protocol_fee_bps)?, fee(creator_fee_bps)?))
+}
+
+// Smallest gross amount whose fees (as calculate_fees charges them) leave at
+// least `net`
+fn gross_for_net(net: u64, protocol_fee_bps: u16, creator_fee_bps: u16) -> Result<u64> {
+    let kept_bps = BPS_DENOMINATOR - protocol_fee_bps as u128 - creator_fee_bps as u128;
+    let mut gross = u64::try_from((net as u128 * BPS_DENOMINATOR).div_ceil(kept_bps))
+        .map_err(|_| ErrorCode::MathOverflow)?;
+
+    // Each fee rounds down on its own, so a unit or two less may still do
+    while gross > net {
+        let (protocol_fee, creator_fee) = calculate_fees(gross - 1, protocol_fee_bps, creator_fee_bps)?;
+        if gross - 1 - protocol_fee - creator_fee < net {
+            break;
+        }
+        gross -= 1;
+    }
+
+    Ok(gross)
+}
+
+// Everything a trade would do to the market, computed without touching it.
+// Buys take fees off the top of the USDC paid; sells take them out of the
+// curve proceeds before they reach the seller.
+//
+// With `exact_output` unset, `amount` is the input: USDC spent on a buy or
+// tokens sold. With it set, `amount` is the output: tokens bought or USDC
+// received after fees, and the quote works out the input from the curve
+// integral, rounding in the pool's favour (any excess stays in the pool).
+// Either way the returned quote's `amount` is the input.
+fn quote_trade(
+    market: &MarketV2,
+    team: u8,
+    action: TradeAction,
+    amount: u64,
+    exact_output: bool,
+    now: i64,
+) -> Result<TradeQuote> {
+    require!(!market.trading_halted, ErrorCode::TradingHalted);
+    require!(now < market.trading_close_ts, ErrorCode::TradingClosed);
+    require!(team <= 1, ErrorCode::InvalidTeam);
+
+    let curve = market.curve(team);
+    let supply = if team == 0 { market.team_a_supply } else { market.team_b_supply };
+    let price_before = curve.spot_price(supply)?;
+    let fees = |gross: u64| calculate_fees(gross, market.protocol_fee_bps, market.creator_fee_bps);
+
+    let (input, tokens_out, usdc_out, protocol_fee, creator_fee) = match (action, exact_output) {
+        (TradeAction::Buy, false) => {
+            let (protocol_fee, creator_fee) = fees(amount)?;
+            let tokens_out = curve.tokens_for_usdc(supply, amount - protocol_fee - creator_fee)?;
+            (amount, tokens_out, 0, protocol_fee, creator_fee)
+        }
+        (TradeAction::Buy, true) => {
+            let cost = curve.buy_cost(supply, amount)?;
+            let usdc_in = gross_for_net(cost, market.protocol_fee_bps, market.creator_fee_bps)?;
+            let (protocol_fee, creator_fee) = fees(usdc_in)?;
+            (usdc_in, amount, 0, protocol_fee, creator_fee)
+        }
+        (TradeAction::Sell, false) => {
+            require!(amount <= supply, ErrorCode::InsufficientSupply);
+            let proceeds = curve.sell_proceeds(supply, amount)?;
+            require!(proceeds <= market.side_pool(team), ErrorCode::InsufficientPoolBalance);
+            let (protocol_fee, creator_fee) = fees(proceeds)?;
+            (amount, 0, proceeds - protocol_fee - creator_fee, protocol_fee, creator_fee)
+        }
+        (TradeAction::Sell, true) => {
+            let proceeds = gross_for_net(amount, market.protocol_fee_bps, market.creator_fee_bps)?;
+            require!(proceeds <= market.side_pool(team), ErrorCode::InsufficientPoolBalance);
+            require!(curve.sell_proceeds(supply, supply)? >= proceeds, ErrorCode::InsufficientSupply);
+
+            // Fewest tokens whose proceeds cover it; proceeds grow with the amount
+            let (mut lo, mut hi) = (0u64, supply);
+            while lo < hi {
+                let mid = lo + (hi - lo) / 2;
+                if curve.sell_proceeds(supply, mid)? >= proceeds {
+                    hi = mid;
+                } else {
+                    lo = mid + 1;
+                }
+            }
+            let (protocol_fee, creator_fee) = fees(proceeds)?;
+            (lo, 0, amount, protocol_fee, creator_fee)
+        }
+    };
+    let supply_after = match action {
+        TradeAction::Buy => supply.checked_add(tokens_out).ok_or(ErrorCode::MathOverflow)?,
+        TradeAction::Sell => supply - input,
+    };
+    let price_after = curve.spot_price(supply_after)?;
+
+    // USDC paid or received per whole token, fees included
+    let (tokens, usdc) = match action {
+        TradeAction::Buy => (tokens_out, input),
+        TradeAction::Sell => (input, usdc_out),
+    };
+    let average_price = if tokens == 0 {
+        0
+    } else {
+        u64::try_from(usdc as u128 * TOKEN_SCALE / tokens as u128).unwrap_or(u64::MAX)
+    };
+    let price_impact_bps = if price_before == 0 {
+        0
+    } else {
+        let moved = price_after.abs_diff(price_before) as u128 * BPS_DENOMINATOR;
+        u64::try_from(moved / price_before as u128).unwrap_or(u64::MAX)
+    };
+
+    Ok(TradeQuote {
+        team,
+        action,
+        amount: input,
+        tokens_out,
+        usdc_out,
+        average_price,
+        price_before,
+        price_after,
+        price_impact_bps,
+        fee: protocol_fee + creator_fee,
+        protocol_fee,
+        creator_fee,
+    })
+}
+
+// Bonding curves
+//
+// Every MarketV2 prices both sides on the curve picked at creation. All curves
+// share the units of sportsxchange_math::linear: prices are USDC base units per
+// whole token, supplies and amounts are token base units. Curves only ever
+// rise with supply.
+//
+// Rounding always favours the pool: buy_cost rounds up, sell_proceeds rounds
+// down, and tokens_for_usdc returns the most tokens whose buy_cost fits in the
+// payment. Selling what was just bought therefore never returns more than was
+// paid.
+pub trait BondingCurve {
+    // Marginal price at `supply`
+    fn spot_price(&self, supply: u64) -> Result<u64>;
+
+    // USDC needed to mint `amount` tokens on top of `supply`, rounded up
+    fn buy_cost(&self, supply: u64, amount: u64) -> Result<u64>;
+
+    // USDC paid for burning `amount` of `supply`, rounded down
+    fn sell_proceeds(&self, supply: u64, amount: u64) -> Result<u64>;
+
+    // Most tokens `usdc_amount` buys at `supply`. Curves without a closed-form
+    // inverse binary-search buy_cost; the spot price at `supply` is the
+    // cheapest any of those tokens can be, which bounds the search.
+    fn tokens_for_usdc(&self, supply: u64, usdc_amount: u64) -> Result<u64> {
+        let mut hi = u64::MAX - supply;
+        let price = self.spot_price(supply)?;
+        if price > 0 {
+            let bound = (usdc_amount as u128) * TOKEN_SCALE / price as u128;
+            hi = hi.min(u64::try_from(bound).unwrap_or(u64::MAX));
+        }
+
+        let mut lo = 0u64;
+        while lo < hi {
+            let mid = lo + (hi - lo).div_ceil(2);
+            // An overflowing cost is more than any u64 payment
+            let affordable = self
+                .buy_cost(supply, mid)
+                .is_ok_and(|cost| cost <= usdc_amount);
+            if affordable {
+                lo = mid;
+            } else {
+                hi = mid - 1;
+            }
+        }
+
+        Ok(lo)
+    }
+}
+
+// Curve shape stored on the market. `base_price` and `slope` live on the
+// market itself; each variant documents how it uses them.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CurveType {
+    // base_price + slope * s
+    Linear,
+    // base_price + slope * s^(exponent / 100) (s in whole tokens), so
+    // fractional exponents such as 150 = 1.5 are allowed
+    Power { exponent: u16 },
+    // base_price + slope * (1 + x / sqrt(1 + x^2)) / 2, x = (s - midpoint) / width:
+    // an S-curve rising from base_price to base_price + slope, steepest at
+    // `midpoint`; `width` is in token base units
+    Sigmoid { midpoint: u64, width: u64 },
+    // base_price at every supply; slope is unused
+    Constant,
+    // Logarithmic Market Scoring Rule with liquidity parameter `liquidity`
+    // (b, in USDC base units): both sides share one cost function, so their
+    // prices always sum to 1 USDC. The creator deposits b * ln 2 at creation,
+    // which is also the most they can lose. base_price and slope are unused.
+    Lmsr { liquidity: u64 },
+}
+
+impl CurveType {
+    pub const MAX_POWER_EXPONENT: u16 = power::MAX_EXPONENT;
+    const SPACE: usize = 1 + 8 + 8;
+
+    fn validate(&self, base_price: u64, slope: u64) -> Result<()> {
+        // A flat zero price cannot price a trade
+        let priced = base_price > 0 || slope > 0;
+        let valid = match *self {
+            CurveType::Linear => priced,
+            CurveType::Power { exponent } => priced && (1..=Self::MAX_POWER_EXPONENT).contains(&exponent),
+            CurveType::Sigmoid { width, .. } => priced && width > 0,
+            CurveType::Constant => base_price > 0,
+            CurveType::Lmsr { liquidity } => liquidity >= lmsr::MIN_LIQUIDITY,
+        };
+        require!(valid, ErrorCode::InvalidCurveParams);
+        Ok(())
+    }
+
+    // USDC the creator must deposit into the pool at creation
+    fn subsidy(&self) -> Result<u64> {
+        match *self {
+            CurveType::Lmsr { liquidity } => lmsr::subsidy(liquidity),
+            _ => Ok(0),
+        }
+    }
+
+    // `other_supply` is the opposite side's supply; only LMSR prices use it
+    fn build(self, base_price: u64, slope: u64, other_supply: u64) -> Box<dyn BondingCurve> {
+        match self {
+            CurveType::Linear => Box::new(LinearCurve { base_price, slope }),
+            CurveType::Power { exponent } => Box::new(PowerCurve { base_price, slope, exponent }),
+            CurveType::Sigmoid { midpoint, width } => Box::new(SigmoidCurve { base_price, slope, midpoint, width }),
+            CurveType::Constant => Box::new(ConstantCurve { price: base_price }),
+            CurveType::Lmsr { liquidity } => Box::new(LmsrCurve { liquidity, other_supply }),
+        }
+    }
+}
+
+// The linear and power curves live in sportsxchange_math so clients run the
+// same code; their errors map onto the ErrorCode of the same name
+impl From<MathError> for ErrorCode {
+    fn from(error: MathError) -> Self {
+        match error {
+            MathError::MathOverflow => ErrorCode::MathOverflow,
+            MathError::InsufficientSupply => ErrorCode::InsufficientSupply,
+        }
+    }
+}
+
+fn math<T>(result: core::result::Result<T, MathError>) -> Result<T> {
+    result.map_err(|error| ErrorCode::from(error).into())
+}
+
+impl BondingCurve for LinearCurve {
+    fn spot_price(&self, supply: u64) -> Result<u64> {
+        math(LinearCurve::spot_price(self, supply))
+    }
+
+    fn buy_cost(&self, supply: u64, amount: u64) -> Result<u64> {
+        math(LinearCurve::buy_cost(self, supply, amount))
+    }
+
+    fn sell_proceeds(&self, supply: u64, amount: u64) -> Result<u64> {
+        math(LinearCurve::sell_proceeds(self, supply, amount))
+    }
+
+    fn tokens_for_usdc(&self, supply: u64, usdc_amount: u64) -> Result<u64> {
+        math(LinearCurve::tokens_for_usdc(self, supply, usdc_amount))
+    }
+}
+
+impl BondingCurve for PowerCurve {
+    fn spot_price(&self, supply: u64) -> Result<u64> {
+        math(PowerCurve::spot_price(self, supply))
+    }
+
+    fn buy_cost(&self, supply: u64, amount: u64) -> Result<u64> {
+        math(PowerCurve::buy_cost(self, supply, amount))
+    }
+
+    fn sell_proceeds(&self, supply: u64, amount: u64) -> Result<u64> {
+        math(PowerCurve::sell_proceeds(self, supply, amount))
+    }
+
+    fn tokens_for_usdc(&self, supply: u64, usdc_amount: u64) -> Result<u64> {
+        math(PowerCurve::tokens_for_usdc(self, supply, usdc_amount))
+    }
+}
+
+pub struct ConstantCurve {
+    pub price: u64,
+}
+
+impl BondingCurve for ConstantCurve {
+    fn spot_price(&self, _supply: u64) -> Result<u64> {
+        Ok(self.price)
+    }
+
+    fn buy_cost(&self, _supply: u64, amount: u64) -> Result<u64> {
+        let cost = (self.price as u128 * amount as u128).div_ceil(TOKEN_SCALE);
+        u64::try_from(cost).map_err(|_| ErrorCode::MathOverflow.into())
+    }
+
+    fn sell_proceeds(&self, supply: u64, amount: u64) -> Result<u64> {
+        require!(amount <= supply, ErrorCode::InsufficientSupply);
+        Ok((self.price as u128 * amount as u128 / TOKEN_SCALE) as u64)
+    }
+
+    fn tokens_for_usdc(&self, _supply: u64, usdc_amount: u64) -> Result<u64> {
+        let tokens = usdc_amount as u128 * TOKEN_SCALE / self.price as u128;
+        u64::try_from(tokens).map_err(|_| ErrorCode::MathOverflow.into())
+    }
+}
+
+// price(s) = base_price + slope * (1 + d / D(s)) / 2 with d = s - midpoint and
+// D(s) = sqrt(width^2 + d^2). D is the antiderivative of d / D, so
+//
+//   cost = (2 * base_price * (s1 - s0) + slope * (s1 - s0 + D(s1) - D(s0))) / (2 * 10^6)
+pub struct SigmoidCurve {
+    pub base_price: u64,
+    pub slope: u64,
+    pub midpoint: u64,
+    pub width: u64,
+}
+
+impl SigmoidCurve {
+    fn distance(&self, supply: u64, round_up: bool) -> Result<u128> {
+        let d = supply.abs_diff(self.midpoint) as u128;
+        let square = (self.width as u128)
+            .checked_mul(self.width as u128)
+            .and_then(|w2| w2.checked_add(d.checked_mul(d)?))
+            .ok_or(ErrorCode::MathOverflow)?;
+        let root = isqrt(square);
+        Ok(if round_up && root * root < square { root + 1 } else { root })
+    }
+
+    // cost * 2 * 10^6, an upper (round_up) or lower bound of the exact integral
+    fn cost_scaled(&self, s0: u64, s1: u64, round_up: bool) -> Result<u128> {
+        let delta = (s1 - s0) as u128;
+        // delta + D(s1) - D(s0) >= 0 because |dD/ds| < 1
+        let rise = (delta + self.distance(s1, round_up)?).saturating_sub(self.distance(s0, !round_up)?);
+
+        (self.base_price as u128)
+            .checked_mul(2 * delta)
+            .and_then(|v| v.checked_add((self.slope as u128).checked_mul(rise)?))
+            .ok_or(ErrorCode::MathOverflow.into())
+    }
+}
+
+impl BondingCurve for SigmoidCurve {
+    fn spot_price(&self, supply: u64) -> Result<u64> {
+        let distance = self.distance(supply, false)?;
+        // distance >= |d|, so the numerator below never goes negative
+        let numerator = if supply >= self.midpoint {
+            distance + (supply - self.midpoint) as u128
+        } else {
+            distance - (self.midpoint - supply) as u128
+        };
+        let price = (self.slope as u128)
+            .checked_mul(numerator)
+            .ok_or(ErrorCode::MathOverflow)?
+            / (2 * distance)
+            + self.base_price as u128;
+        u64::try_from(price).map_err(|_| ErrorCode::MathOverflow.into())
+    }
+
+    fn buy_cost(&self, supply: u64, amount: u64) -> Result<u64> {
+        let end = supply.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+        let cost = self.cost_scaled(supply, end, true)?.div_ceil(2 * TOKEN_SCALE);
+        u64::try_from(cost).map_err(|_| ErrorCode::MathOverflow.into())
+    }
+
+    fn sell_proceeds(&self, supply: u64, amount: u64) -> Result<u64> {
+        let start = supply.checked_sub(amount).ok_or(ErrorCode::InsufficientSupply)?;
+        let proceeds = self.cost_scaled(start, supply, false)? / (2 * TOKEN_SCALE);
+        u64::try_from(proceeds).map_err(|_| ErrorCode::MathOverflow.into())
+    }
+}
+
+// Account structures
+#[derive(Accounts)]
+#[instruction(game_id: String)]
+pub struct CreateMarketV2<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + MarketV2::SPACE,
+        seeds = [b"market", game_id.as_bytes()],
+        bump
+    )]
+    pub market: Account<'info, MarketV2>,
+    
+    #[account(
+        init,
+        payer = authority,
+        mint::decimals = 6,
+        mint::authority = market,
+        seeds = [b"team_a_mint", game_id.as_bytes()],
+        bump
+    )]
+    pub team_a_mint: Account<'info, Mint>,
+    
+    #[account(
+        init,
+        payer = authority,
+        mint::decimals = 6,
+        mint::authority = market,
+        seeds = [b"team_b_mint", game_id.as_bytes()],
+        bump
+    )]
+    pub team_b_mint: Account<'info, Mint>,
+    
+    #[account(
+        init,
+        payer = authority,
+        token::mint = usdc_mint,
+        token::authority = market,
+        seeds = [b"usdc_vault", game_id.as_bytes()],
+        bump
+    )]
+    pub usdc_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        init,
+        payer = authority,
+        token::mint = usdc_mint,
+        token::authority = market,
+        seeds = [b"fee_vault", game_id.as_bytes()],
+        bump
+    )]
+    pub fee_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProtocolPaused,
+        constraint = config.market_creator == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+    
+    #[account(
+        constraint = config.allowed_collateral_mints.contains(&usdc_mint.key()) @ ErrorCode::CollateralNotAllowed
+    )]
+    pub usdc_mint: Account<'info, Mint>,
+
+    // Pays the creator subsidy; only required for LMSR markets
+    #[account(
+        mut,
+        token::mint = usdc_mint,
+        token::authority = authority
+    )]
+    pub authority_usdc: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + ProtocolConfig::SPACE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::Sportsxchange>,
+
+    #[account(constraint = program_data.upgrade_authority_address == Some(admin.key()) @ ErrorCode::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+}
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = authority.key() == config.pauser || authority.key() == config.admin @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+}
+
+#[derive(Accounts)]
+pub struct BuyOnCurve<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProtocolPaused
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+    
+    #[account(
+        mut,
+        seeds = [b"market", market.game_id.as_bytes()],
+        bump,
+        has_one = team_a_mint,
+        has_one = team_b_mint,
+        has_one = usdc_vault,
+        has_one = fee_vault
+    )]
+    pub market: Account<'info, MarketV2>,
+    
+    #[account(
+        mut,
+        seeds = [b"team_a_mint", market.game_id.as_bytes()],
+        bump
+    )]
+    pub team_a_mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"team_b_mint", market.game_id.as_bytes()],
+        bump
+    )]
+    pub team_b_mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        associated_token::mint = team_a_mint,
+        associated_token::authority = buyer
+    )]
+    pub buyer_team_a_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        associated_token::mint = team_b_mint,
+        associated_token::authority = buyer
+    )]
+    pub buyer_team_b_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = market.usdc_mint,
+        token::authority = buyer
+    )]
+    pub buyer_usdc: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"usdc_vault", market.game_id.as_bytes()],
+        bump,
+        token::mint = market.usdc_mint
+    )]
+    pub usdc_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"fee_vault", market.game_id.as_bytes()],
+        bump
+    )]
+    pub fee_vault: Account<'info, TokenAccount>,
+
+    // Optional trade record (see open_position). Checked by its stored market
+    // and user rather than seeds, so clients that don't track positions can
+    // leave it out.
+    #[account(
+        mut,
+        constraint = position.market == market.key() && position.user == buyer.key() @ ErrorCode::InvalidPosition
+    )]
+    pub position: Option<Account<'info, UserPosition>>,
+    
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SellOnCurve<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProtocolPaused
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+    
+    #[account(
+        mut,
+        seeds = [b"market", market.game_id.as_bytes()],
+        bump,
+        has_one = team_a_mint,
+        has_one = team_b_mint,
+        has_one = usdc_vault,
+        has_one = fee_vault
+    )]
+    pub market: Account<'info, MarketV2>,
+    
+    #[account(
+        mut,
+        seeds = [b"team_a_mint", market.game_id.as_bytes()],
+        bump
+    )]
+    pub team_a_mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"team_b_mint", market.game_id.as_bytes()],
+        bump
+    )]
+    pub team_b_mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        associated_token::mint = team_a_mint,
+        associated_token::authority = seller
+    )]
+    pub seller_team_a_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        associated_token::mint = team_b_mint,
+        associated_token::authority = seller
+    )]
+    pub seller_team_b_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = market.usdc_mint,
+        token::authority = seller
+    )]
+    pub seller_usdc: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"usdc_vault", market.game_id.as_bytes()],
+        bump,
+        token::mint = market.usdc_mint
+    )]
+    pub usdc_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"fee_vault", market.game_id.as_bytes()],
+        bump
+    )]
+    pub fee_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = position.market == market.key() && position.user == seller.key() @ ErrorCode::InvalidPosition
+    )]
+    pub position: Option<Account<'info, UserPosition>>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct HaltTrading<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = authority.key() == market.authority || authority.key() == config.pauser @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.game_id.as_bytes()],
+        bump
+    )]
+    pub market: Account<'info, MarketV2>,
+}
+
+#[derive(Accounts)]
+pub struct ExtendTradingClose<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.game_id.as_bytes()],
+        bump,
+        has_one = authority
+    )]
+    pub market: Account<'info, MarketV2>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveMarket<'info> {
+    pub resolver: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = resolver
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.game_id.as_bytes()],
+        bump
+    )]
+    pub market: Account<'info, MarketV2>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: String)]
+pub struct PublishOracleResult<'info> {
+    #[account(mut)]
+    pub publisher: Signer<'info>,
+
+    #[account(
+        init,
+        payer = publisher,
+        space = 8 + OracleResult::SPACE,
+        seeds = [b"oracle_result", publisher.key().as_ref(), game_id.as_bytes()],
+        bump
+    )]
+    pub oracle_result: Account<'info, OracleResult>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateOracleResult<'info> {
+    pub publisher: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"oracle_result", publisher.key().as_ref(), oracle_result.game_id.as_bytes()],
+        bump = oracle_result.bump,
+        has_one = publisher
+    )]
+    pub oracle_result: Account<'info, OracleResult>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveFromOracle<'info> {
+    #[account(
+        mut,
+        seeds = [b"market", market.game_id.as_bytes()],
+        bump
+    )]
+    pub market: Account<'info, MarketV2>,
+
+    // The feed for this game from the publisher the market named
+    #[account(
+        seeds = [b"oracle_result", oracle_result.publisher.as_ref(), market.game_id.as_bytes()],
+        bump = oracle_result.bump,
+        constraint = market.oracle == Some(oracle_result.publisher) @ ErrorCode::InvalidOracle
+    )]
+    pub oracle_result: Account<'info, OracleResult>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeOutcome<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.game_id.as_bytes()],
+        bump,
+        has_one = usdc_mint
+    )]
+    pub market: Account<'info, MarketV2>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + ResolutionProposal::SPACE,
+        seeds = [b"proposal", market.key().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, ResolutionProposal>,
+
+    // Holds the proposer's and any disputer's bonds
+    #[account(
+        init,
+        payer = proposer,
+        token::mint = usdc_mint,
+        token::authority = market,
+        seeds = [b"bond_vault", market.key().as_ref()],
+        bump
+    )]
+    pub bond_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = usdc_mint,
+        token::authority = proposer
+    )]
+    pub proposer_usdc: Account<'info, TokenAccount>,
+
+    pub usdc_mint: Account<'info, Mint>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DisputeOutcome<'info> {
+    pub disputer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.game_id.as_bytes()],
+        bump
+    )]
+    pub market: Account<'info, MarketV2>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", market.key().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, ResolutionProposal>,
+
+    #[account(
+        mut,
+        seeds = [b"bond_vault", market.key().as_ref()],
+        bump
+    )]
+    pub bond_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = market.usdc_mint,
+        token::authority = disputer
+    )]
+    pub disputer_usdc: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeOutcome<'info> {
+    #[account(
+        mut,
+        seeds = [b"market", market.game_id.as_bytes()],
+        bump
+    )]
+    pub market: Account<'info, MarketV2>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", market.key().as_ref()],
+        bump = proposal.bump,
+        has_one = proposer,
+        close = proposer
+    )]
+    pub proposal: Account<'info, ResolutionProposal>,
+
+    /// CHECK: receives the proposal's rent; checked against proposal.proposer
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"bond_vault", market.key().as_ref()],
+        bump
+    )]
+    pub bond_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = market.usdc_mint,
+        token::authority = proposer
+    )]
+    pub proposer_usdc: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ArbitrateOutcome<'info> {
+    pub arbiter: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = arbiter
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.game_id.as_bytes()],
+        bump
+    )]
+    pub market: Account<'info, MarketV2>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", market.key().as_ref()],
+        bump = proposal.bump,
+        has_one = proposer,
+        close = proposer
+    )]
+    pub proposal: Account<'info, ResolutionProposal>,
+
+    /// CHECK: receives the proposal's rent; checked against proposal.proposer
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"bond_vault", market.key().as_ref()],
+        bump
+    )]
+    pub bond_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = market.usdc_mint,
+        token::authority = arbiter
+    )]
+    pub arbiter_usdc: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(name: String)]
+pub struct CreateCommittee<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + ResolverCommittee::SPACE,
+        seeds = [b"committee", name.as_bytes()],
+        bump
+    )]
+    pub committee: Account<'info, ResolverCommittee>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AssignCommittee<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.game_id.as_bytes()],
+        bump
+    )]
+    pub market: Account<'info, MarketV2>,
+
+    #[account(
+        seeds = [b"committee", committee.name.as_bytes()],
+        bump = committee.bump
+    )]
+    pub committee: Account<'info, ResolverCommittee>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + CommitteeBallot::SPACE,
+        seeds = [b"ballot", market.key().as_ref()],
+        bump
+    )]
+    pub ballot: Account<'info, CommitteeBallot>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VoteOutcome<'info> {
+    pub voter: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.game_id.as_bytes()],
+        bump
+    )]
+    pub market: Account<'info, MarketV2>,
+
+    #[account(
+        mut,
+        seeds = [b"ballot", market.key().as_ref()],
+        bump = ballot.bump,
+        has_one = committee
+    )]
+    pub ballot: Account<'info, CommitteeBallot>,
+
+    pub committee: Account<'info, ResolverCommittee>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveContested<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.game_id.as_bytes()],
+        bump
+    )]
+    pub market: Account<'info, MarketV2>,
+}
+
+#[derive(Accounts)]
+pub struct VoidMarket<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = authority.key() == market.authority || authority.key() == config.resolver @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.game_id.as_bytes()],
+        bump
+    )]
+    pub market: Account<'info, MarketV2>,
+}
+
+#[derive(Accounts)]
+pub struct Claim<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProtocolPaused
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.game_id.as_bytes()],
+        bump,
+        has_one = team_a_mint,
+        has_one = team_b_mint,
+        has_one = usdc_vault
+    )]
+    pub market: Account<'info, MarketV2>,
+
+    #[account(
+        mut,
+        seeds = [b"team_a_mint", market.game_id.as_bytes()],
+        bump
+    )]
+    pub team_a_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"team_b_mint", market.game_id.as_bytes()],
+        bump
+    )]
+    pub team_b_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = team_a_mint,
+        associated_token::authority = user
+    )]
+    pub user_team_a_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = team_b_mint,
+        associated_token::authority = user
+    )]
+    pub user_team_b_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = market.usdc_mint,
+        token::authority = user
+    )]
+    pub user_usdc: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"usdc_vault", market.game_id.as_bytes()],
+        bump,
+        token::mint = market.usdc_mint
+    )]
+    pub usdc_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Quote<'info> {
+    #[account(
+        seeds = [b"market", market.game_id.as_bytes()],
+        bump
+    )]
+    pub market: Account<'info, MarketV2>,
+}
+
+#[derive(Accounts)]
+pub struct CompleteSet<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProtocolPaused
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.game_id.as_bytes()],
+        bump,
+        has_one = team_a_mint,
+        has_one = team_b_mint,
+        has_one = usdc_vault
+    )]
+    pub market: Account<'info, MarketV2>,
+
+    #[account(
+        mut,
+        seeds = [b"team_a_mint", market.game_id.as_bytes()],
+        bump
+    )]
+    pub team_a_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"team_b_mint", market.game_id.as_bytes()],
+        bump
+    )]
+    pub team_b_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = team_a_mint,
+        associated_token::authority = user
+    )]
+    pub user_team_a_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = team_b_mint,
+        associated_token::authority = user
+    )]
+    pub user_team_b_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = market.usdc_mint,
+        token::authority = user
+    )]
+    pub user_usdc: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"usdc_vault", market.game_id.as_bytes()],
+        bump,
+        token::mint = market.usdc_mint
+    )]
+    pub usdc_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct OpenPosition<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"market", market.game_id.as_bytes()],
+        bump
+    )]
+    pub market: Account<'info, MarketV2>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + UserPosition::SPACE,
+        seeds = [b"position", market.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, UserPosition>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClosePosition<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"position", position.market.as_ref(), user.key().as_ref()],
+        bump = position.bump,
+        has_one = user,
+        close = user
+    )]
+    pub position: Account<'info, UserPosition>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawProtocolFees<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.game_id.as_bytes()],
+        bump,
+        has_one = fee_vault
+    )]
+    pub market: Account<'info, MarketV2>,
+
+    #[account(mut)]
+    pub fee_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = market.usdc_mint,
+        token::authority = config.treasury
+    )]
+    pub destination: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawCreatorFees<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.game_id.as_bytes()],
+        bump,
+        has_one = authority,
+        has_one = fee_vault
+    )]
+    pub market: Account<'info, MarketV2>,
+
+    #[account(mut)]
+    pub fee_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = market.usdc_mint,
+        token::authority = authority
+    )]
+    pub destination: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawSubsidy<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.game_id.as_bytes()],
+        bump,
+        has_one = authority,
+        has_one = usdc_vault
+    )]
+    pub market: Account<'info, MarketV2>,
+
+    #[account(mut)]
+    pub usdc_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = market.usdc_mint,
+        token::authority = authority
+    )]
+    pub destination: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: String)]
+pub struct CreateMultiMarket<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + MultiMarket::SPACE,
+        seeds = [b"multi_market", game_id.as_bytes()],
+        bump
+    )]
+    pub market: Account<'info, MultiMarket>,
+
+    #[account(
+        init,
+        payer = authority,
+        token::mint = usdc_mint,
+        token::authority = market,
+        seeds = [b"multi_vault", game_id.as_bytes()],
+        bump
+    )]
+    pub usdc_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = authority,
+        token::mint = usdc_mint,
+        token::authority = market,
+        seeds = [b"multi_fee_vault", game_id.as_bytes()],
+        bump
+    )]
+    pub fee_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProtocolPaused,
+        constraint = config.market_creator == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        constraint = config.allowed_collateral_mints.contains(&usdc_mint.key()) @ ErrorCode::CollateralNotAllowed
+    )]
+    pub usdc_mint: Account<'info, Mint>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct AddOutcome<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"multi_market", market.game_id.as_bytes()],
+        bump,
+        has_one = authority
+    )]
+    pub market: Account<'info, MultiMarket>,
+
+    #[account(
+        init,
+        payer = authority,
+        mint::decimals = 6,
+        mint::authority = market,
+        seeds = [b"outcome_mint", market.game_id.as_bytes(), &[market.outcomes.len() as u8]],
+        bump
+    )]
+    pub outcome_mint: Account<'info, Mint>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(outcome: u8)]
+pub struct BuyOutcome<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProtocolPaused
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"multi_market", market.game_id.as_bytes()],
+        bump,
+        has_one = usdc_vault,
+        has_one = fee_vault
+    )]
+    pub market: Account<'info, MultiMarket>,
+
+    #[account(
+        mut,
+        seeds = [b"outcome_mint", market.game_id.as_bytes(), &[outcome]],
+        bump
+    )]
+    pub outcome_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = outcome_mint,
+        associated_token::authority = buyer
+    )]
+    pub buyer_outcome_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = market.usdc_mint,
+        token::authority = buyer
+    )]
+    pub buyer_usdc: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"multi_vault", market.game_id.as_bytes()],
+        bump,
+        token::mint = market.usdc_mint
+    )]
+    pub usdc_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"multi_fee_vault", market.game_id.as_bytes()],
+        bump
+    )]
+    pub fee_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(outcome: u8)]
+pub struct SellOutcome<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProtocolPaused
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"multi_market", market.game_id.as_bytes()],
+        bump,
+        has_one = usdc_vault,
+        has_one = fee_vault
+    )]
+    pub market: Account<'info, MultiMarket>,
+
+    #[account(
+        mut,
+        seeds = [b"outcome_mint", market.game_id.as_bytes(), &[outcome]],
+        bump
+    )]
+    pub outcome_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = outcome_mint,
+        associated_token::authority = seller
+    )]
+    pub seller_outcome_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = market.usdc_mint,
+        token::authority = seller
+    )]
+    pub seller_usdc: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"multi_vault", market.game_id.as_bytes()],
+        bump,
+        token::mint = market.usdc_mint
+    )]
+    pub usdc_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"multi_fee_vault", market.game_id.as_bytes()],
+        bump
+    )]
+    pub fee_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct HaltMultiMarket<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = authority.key() == market.authority || authority.key() == config.pauser @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"multi_market", market.game_id.as_bytes()],
+        bump
+    )]
+    pub market: Account<'info, MultiMarket>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveMultiMarket<'info> {
+    pub resolver: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = resolver
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"multi_market", market.game_id.as_bytes()],
+        bump
+    )]
+    pub market: Account<'info, MultiMarket>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimMultiWinnings<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProtocolPaused
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"multi_market", market.game_id.as_bytes()],
+        bump,
+        has_one = usdc_vault
+    )]
+    pub market: Account<'info, MultiMarket>,
+
+    // Only the winning outcome's mint derives here once the market resolves
+    #[account(
+        mut,
+        seeds = [b"outcome_mint", market.game_id.as_bytes(), &[market.winner.unwrap_or_default()]],
+        bump
+    )]
+    pub outcome_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = outcome_mint,
+        associated_token::authority = user
+    )]
+    pub user_outcome_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = market.usdc_mint,
+        token::authority = user
+    )]
+    pub user_usdc: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"multi_vault", market.game_id.as_bytes()],
+        bump
+    )]
+    pub usdc_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawMultiProtocolFees<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"multi_market", market.game_id.as_bytes()],
+        bump,
+        has_one = fee_vault
+    )]
+    pub market: Account<'info, MultiMarket>,
+
+    #[account(mut)]
+    pub fee_vault: Account<'info, TokenAccount>,
 
-    // Create market with simpler linear bonding curve
-    pub fn create_market_v2(
-        ctx: Context<CreateMarketV2>,
-        game_id: String,
-        team_a: String,
-        team_b: String,
-        base_price: u64,  // Base price in lamports per token (e.g., 100 = 0.0001 SOL)
-        slope: u64,       // Price increase per million tokens (e.g., 10 = price goes up 0.00001 SOL per million tokens)
-    ) -> Result<()> {
-        let market = &mut ctx.accounts.market;
-        market.authority = ctx.accounts.authority.key();
-        market.game_id = game_id;
-        market.team_a = team_a;
-        market.team_b = team_b;
-        market.team_a_mint = ctx.accounts.team_a_mint.key();
-        market.team_b_mint = ctx.accounts.team_b_mint.key();
-        market.usdc_vault = ctx.accounts.usdc_vault.key();
-        market.base_price = base_price;
-        market.slope = slope;
-        market.team_a_supply = 0;
-        market.team_b_supply = 0;
-        market.pool_value = 0;
-        market.is_resolved = false;
-        market.winner = None;
-        market.trading_halted = false;
-        
-        msg!("Market created: {} vs {}", market.team_a, market.team_b);
-        msg!("Linear bonding curve: base_price={}, slope={}", base_price, slope);
-        
-        Ok(())
+    #[account(
+        mut,
+        token::mint = market.usdc_mint,
+        token::authority = config.treasury
+    )]
+    pub destination: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawMultiCreatorFees<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"multi_market", market.game_id.as_bytes()],
+        bump,
+        has_one = authority,
+        has_one = fee_vault
+    )]
+    pub market: Account<'info, MultiMarket>,
+
+    #[account(mut)]
+    pub fee_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = market.usdc_mint,
+        token::authority = authority
+    )]
+    pub destination: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+// State
+#[account]
+pub struct MarketV2 {
+    pub authority: Pubkey,
+    pub game_id: String,  // 50 chars max
+    pub team_a: String,   // 20 chars max
+    pub team_b: String,   // 20 chars max
+    pub team_a_mint: Pubkey,
+    pub team_b_mint: Pubkey,
+    pub usdc_vault: Pubkey,
+    pub usdc_mint: Pubkey,   // Collateral mint the vault holds
+    pub usdc_decimals: u8,
+    pub fee_vault: Pubkey,   // Holds accrued fees, separate from the pool
+    pub protocol_fee_bps: u16,
+    pub creator_fee_bps: u16,
+    pub protocol_fees_accrued: u64,  // Withdrawable by the treasury
+    pub creator_fees_accrued: u64,   // Withdrawable by the market authority
+    pub curve_type: CurveType,  // Shape of both sides' price curves
+    pub kind: MarketKind,       // Moneyline, spread or total
+    pub base_price: u64,     // Base price (starting price)
+    pub slope: u64,          // Price increase per million tokens
+    pub team_a_supply: u64,
+    pub team_b_supply: u64,
+    pub pool_value: u64,
+    pub team_a_pool: u64,    // USDC contributed to the curve by team A trades
+    pub team_b_pool: u64,    // USDC contributed to the curve by team B trades
+    pub subsidy: u64,        // USDC the creator seeded the pool with (LMSR)
+    pub subsidy_surplus: u64,  // Subsidy left over after settlement, withdrawable by the authority
+    pub complete_sets: u64,  // Outstanding A+B pairs, each backed 1:1 in the vault outside pool_value
+    pub trading_close_ts: i64,  // Game kickoff: trades are refused from this unix time on
+    pub oracle: Option<Pubkey>,  // Publisher whose final OracleResult settles the market
+    pub resolution_state: ResolutionState,  // Where an optimistic proposal stands
+    pub scalar_value: Option<i64>,  // Value a scalar market settled at
+    pub trading_halted: bool,
+    pub is_resolved: bool,
+    pub is_voided: bool,
+    pub winner: Option<u8>,
+}
+
+impl MarketV2 {
+    // `winner` value for a drawn game, or a scalar market paying both sides
+    pub const DRAW: u8 = 2;
+
+    // Share of the pool team A is owed for `winner`, as (numerator, denominator)
+    fn winner_share(winner: u8) -> (u64, u64) {
+        match winner {
+            0 => (1, 1),
+            1 => (0, 1),
+            _ => (1, 2),
+        }
     }
 
-    // Buy tokens with linear bonding curve
-    pub fn buy_on_curve(
-        ctx: Context<BuyOnCurve>,
-        team: u8,
-        usdc_amount: u64,
-        min_tokens_out: u64,
-    ) -> Result<()> {
-        let market = &mut ctx.accounts.market;
-        
-        require!(!market.trading_halted, ErrorCode::TradingHalted);
-        require!(team <= 1, ErrorCode::InvalidTeam);
-        
-        let current_supply = if team == 0 { 
-            market.team_a_supply 
-        } else { 
-            market.team_b_supply 
-        };
-        
-        // Calculate tokens using linear bonding curve
-        // Average price = base_price + (slope * current_supply / 2)
-        // tokens_out = usdc_amount / average_price
-        let tokens_out = calculate_tokens_linear(
-            usdc_amount,
-            current_supply,
-            market.base_price,
-            market.slope,
-        )?;
-        
-        require!(tokens_out >= min_tokens_out, ErrorCode::SlippageExceeded);
-        
-        // Transfer USDC
-        let cpi_ctx = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.buyer_usdc.to_account_info(),
-                to: ctx.accounts.usdc_vault.to_account_info(),
-                authority: ctx.accounts.buyer.to_account_info(),
-            },
+    const SPACE: usize = 32 + 4 + 50 + 4 + 20 + 4 + 20 + 32 + 32 + 32 + 32 + 1 + 32 + 2 + 2 + 8 + 8 + CurveType::SPACE + MarketKind::SPACE + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 32 + 1 + 9 + 1 + 1 + 1 + 1 + 1;
+
+    // Price curve for one side of the market
+    pub fn curve(&self, team: u8) -> Box<dyn BondingCurve> {
+        let other_supply = if team == 0 { self.team_b_supply } else { self.team_a_supply };
+        self.curve_type.build(self.base_price, self.slope, other_supply)
+    }
+
+    // Whether `outcome` is one this market can be settled on directly. A
+    // spread or total cannot draw: a push voids it instead. Scalar markets
+    // only settle at a value, through resolve_scalar.
+    fn check_outcome(&self, outcome: u8) -> Result<()> {
+        require!(!matches!(self.kind, MarketKind::Scalar { .. }), ErrorCode::InvalidMarketKind);
+        require!(outcome <= Self::DRAW, ErrorCode::InvalidTeam);
+        require!(
+            outcome != Self::DRAW || self.kind == MarketKind::Moneyline,
+            ErrorCode::InvalidMarketKind
         );
-        token::transfer(cpi_ctx, usdc_amount)?;
-        
-        // Mint tokens
-        let mint = if team == 0 {
-            &ctx.accounts.team_a_mint
+        Ok(())
+    }
+
+    pub fn is_lmsr(&self) -> bool {
+        matches!(self.curve_type, CurveType::Lmsr { .. })
+    }
+
+    // USDC a sell of `team` may draw from. Under LMSR both sides are priced
+    // off one cost function that the whole pool backs, so the pool is only
+    // split into side pools at settlement.
+    fn side_pool(&self, team: u8) -> u64 {
+        if self.is_lmsr() {
+            self.pool_value
+        } else if team == 0 {
+            self.team_a_pool
         } else {
-            &ctx.accounts.team_b_mint
-        };
-        
-        let token_account = if team == 0 {
-            &ctx.accounts.buyer_team_a_account
+            self.team_b_pool
+        }
+    }
+
+    fn add_to_side_pool(&mut self, team: u8, amount: u64) {
+        if self.is_lmsr() {
+            return;
+        }
+        if team == 0 {
+            self.team_a_pool += amount;
         } else {
-            &ctx.accounts.buyer_team_b_account
-        };
-        
-        let seeds = &[
-            b"market",
-            market.game_id.as_bytes(),
-            &[ctx.bumps.market],
-        ];
-        let signer = &[&seeds[..]];
-        
-        let cpi_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            MintTo {
-                mint: mint.to_account_info(),
-                to: token_account.to_account_info(),
-                authority: market.to_account_info(),
-            },
-            signer,
-        );
-        token::mint_to(cpi_ctx, tokens_out)?;
-        
-        // Update state
+            self.team_b_pool += amount;
+        }
+    }
+
+    fn take_from_side_pool(&mut self, team: u8, amount: u64) {
+        if self.is_lmsr() {
+            return;
+        }
         if team == 0 {
-            market.team_a_supply += tokens_out;
+            self.team_a_pool -= amount;
         } else {
-            market.team_b_supply += tokens_out;
+            self.team_b_pool -= amount;
         }
-        market.pool_value += usdc_amount;
-        
-        msg!("Bought {} tokens for {} USDC", tokens_out, usdc_amount);
-        
+    }
+
+    // Other resolution paths and voiding wait while one is under way
+    pub fn check_resolution_idle(&self) -> Result<()> {
+        match self.resolution_state {
+            ResolutionState::Idle => Ok(()),
+            ResolutionState::Proposed | ResolutionState::Disputed => err!(ErrorCode::ProposalPending),
+            ResolutionState::Committee | ResolutionState::Contested => err!(ErrorCode::CommitteeResolution),
+        }
+    }
+
+    // Trading stops at kickoff even if nobody calls halt_trading
+    pub fn is_trading_closed(&self, now: i64) -> bool {
+        self.trading_halted || now >= self.trading_close_ts
+    }
+
+    // At settlement complete sets join the LMSR pool: their collateral is
+    // added to pool_value and their tokens to both supplies, so every token
+    // of a side is paid alike and a set is still worth exactly one USDC.
+    // Bonding-curve markets never hold sets (see mint_complete_set).
+    fn fold_complete_sets(&mut self) -> Result<()> {
+        let sets = self.complete_sets;
+        self.complete_sets = 0;
+        self.team_a_supply = self.team_a_supply.checked_add(sets).ok_or(ErrorCode::MathOverflow)?;
+        self.team_b_supply = self.team_b_supply.checked_add(sets).ok_or(ErrorCode::MathOverflow)?;
+        self.pool_value = self.pool_value.checked_add(sets).ok_or(ErrorCode::MathOverflow)?;
         Ok(())
     }
 
-    // Sell tokens back to the bonding curve
-    pub fn sell_on_curve(
-        ctx: Context<SellOnCurve>,
-        team: u8,
-        token_amount: u64,
-        min_usdc_out: u64,
-    ) -> Result<()> {
-        let market = &mut ctx.accounts.market;
-        
-        require!(!market.trading_halted, ErrorCode::TradingHalted);
-        require!(team <= 1, ErrorCode::InvalidTeam);
-        
-        let current_supply = if team == 0 { 
-            market.team_a_supply 
-        } else { 
-            market.team_b_supply 
-        };
-        
-        require!(token_amount <= current_supply, ErrorCode::InsufficientSupply);
-        
-        // Calculate USDC to return using linear bonding curve
-        let usdc_out = calculate_usdc_linear(
-            token_amount,
-            current_supply,
-            market.base_price,
-            market.slope,
-        )?;
-        
-        require!(usdc_out >= min_usdc_out, ErrorCode::SlippageExceeded);
-        require!(usdc_out <= market.pool_value, ErrorCode::InsufficientPoolBalance);
-        
-        // Burn tokens from seller
-        let mint = if team == 0 {
-            &ctx.accounts.team_a_mint
+    // Fix an LMSR market's side pools for team A's `share` (None when voided)
+    // and set aside the rest of the pool as subsidy surplus
+    fn settle_lmsr(&mut self, share: Option<(u64, u64)>) -> Result<()> {
+        let (team_a_pool, team_b_pool) = lmsr::settlement_pools(self, share)?;
+        self.team_a_pool = team_a_pool;
+        self.team_b_pool = team_b_pool;
+        self.subsidy_surplus = self.pool_value - team_a_pool - team_b_pool;
+        self.pool_value = team_a_pool + team_b_pool;
+        Ok(())
+    }
+}
+
+// One wallet's trading record in one market, seeded by (market, user).
+// Cost basis is average cost: a sell releases basis in proportion to the
+// tokens sold, and realized PnL is proceeds minus the released basis. Tokens
+// moved outside buy/sell (transfers, complete sets, claims) are not tracked.
+#[account]
+pub struct UserPosition {
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub team_a_tokens: u64,
+    pub team_b_tokens: u64,
+    pub team_a_cost_basis: u64,  // USDC paid, fees included, for the tokens still held
+    pub team_b_cost_basis: u64,
+    pub usdc_spent: u64,
+    pub usdc_received: u64,
+    pub realized_pnl: i64,
+    pub first_trade_slot: u64,  // 0 until the first trade
+    pub last_trade_slot: u64,
+    pub bump: u8,
+}
+
+impl UserPosition {
+    const SPACE: usize = 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 1;
+
+    fn side_mut(&mut self, team: u8) -> (&mut u64, &mut u64) {
+        if team == 0 {
+            (&mut self.team_a_tokens, &mut self.team_a_cost_basis)
         } else {
-            &ctx.accounts.team_b_mint
-        };
-        
-        let token_account = if team == 0 {
-            &ctx.accounts.seller_team_a_account
+            (&mut self.team_b_tokens, &mut self.team_b_cost_basis)
+        }
+    }
+
+    fn touch(&mut self, slot: u64) {
+        if self.first_trade_slot == 0 {
+            self.first_trade_slot = slot;
+        }
+        self.last_trade_slot = slot;
+    }
+
+    pub fn record_buy(&mut self, team: u8, usdc_in: u64, tokens_out: u64, slot: u64) -> Result<()> {
+        let (tokens, basis) = self.side_mut(team);
+        *tokens = tokens.checked_add(tokens_out).ok_or(ErrorCode::MathOverflow)?;
+        *basis = basis.checked_add(usdc_in).ok_or(ErrorCode::MathOverflow)?;
+        self.usdc_spent = self.usdc_spent.checked_add(usdc_in).ok_or(ErrorCode::MathOverflow)?;
+        self.touch(slot);
+        Ok(())
+    }
+
+    pub fn record_sell(&mut self, team: u8, tokens_in: u64, usdc_out: u64, slot: u64) -> Result<()> {
+        let (tokens, basis) = self.side_mut(team);
+        // Tokens bought off-record (e.g. received by transfer) carry no basis
+        let tracked = tokens_in.min(*tokens);
+        let released = if tracked == *tokens {
+            *basis
         } else {
-            &ctx.accounts.seller_team_b_account
+            (*basis as u128 * tracked as u128 / *tokens as u128) as u64
         };
-        
-        let cpi_ctx = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Burn {
-                mint: mint.to_account_info(),
-                from: token_account.to_account_info(),
-                authority: ctx.accounts.seller.to_account_info(),
-            },
-        );
-        token::burn(cpi_ctx, token_amount)?;
-        
-        // Transfer USDC back to seller
-        let seeds = &[
-            b"market",
-            market.game_id.as_bytes(),
-            &[ctx.bumps.market],
-        ];
-        let signer = &[&seeds[..]];
-        
-        let cpi_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.usdc_vault.to_account_info(),
-                to: ctx.accounts.seller_usdc.to_account_info(),
-                authority: market.to_account_info(),
-            },
-            signer,
-        );
-        token::transfer(cpi_ctx, usdc_out)?;
-        
-        // Update state
-        if team == 0 {
-            market.team_a_supply -= token_amount;
-        } else {
-            market.team_b_supply -= token_amount;
+        *tokens -= tracked;
+        *basis -= released;
+
+        self.usdc_received = self.usdc_received.checked_add(usdc_out).ok_or(ErrorCode::MathOverflow)?;
+        let pnl = i64::try_from(usdc_out as i128 - released as i128).map_err(|_| ErrorCode::MathOverflow)?;
+        self.realized_pnl = self.realized_pnl.checked_add(pnl).ok_or(ErrorCode::MathOverflow)?;
+        self.touch(slot);
+        Ok(())
+    }
+}
+
+// What a market's two tokens pay out on. Lines are in half points, so -7 is
+// -3.5 and 95 is 47.5; whole-number lines (even values) can push.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MarketKind {
+    // Team A (home) against team B (away); a tie is a draw
+    Moneyline,
+    // Team A covers when its margin plus `line` is positive
+    Spread { line: i32 },
+    // Team A is the over, team B the under on the combined score
+    Total { line: u32 },
+    // Team A is long and team B short a reported value: settled by
+    // resolve_scalar, which splits the pool linearly across [lower, upper]
+    Scalar { lower: i64, upper: i64 },
+}
+
+impl MarketKind {
+    const SPACE: usize = 1 + 8 + 8;
+
+    fn validate(&self) -> Result<()> {
+        if let MarketKind::Scalar { lower, upper } = *self {
+            require!(lower < upper, ErrorCode::InvalidMarketKind);
         }
-        market.pool_value -= usdc_out;
-        
-        msg!("Sold {} tokens for {} USDC", token_amount, usdc_out);
-        
         Ok(())
     }
+
+    // Market `winner` for a final score, or None on a push. Scalar markets
+    // do not settle on scores.
+    pub fn outcome(&self, home_score: u16, away_score: u16) -> Result<Option<u8>> {
+        let (home, away) = (home_score as i64, away_score as i64);
+        let edge = match *self {
+            MarketKind::Moneyline => home - away,
+            MarketKind::Spread { line } => 2 * (home - away) + line as i64,
+            MarketKind::Total { line } => 2 * (home + away) - line as i64,
+            MarketKind::Scalar { .. } => return err!(ErrorCode::InvalidMarketKind),
+        };
+        Ok(match edge.cmp(&0) {
+            std::cmp::Ordering::Greater => Some(0),
+            std::cmp::Ordering::Less => Some(1),
+            std::cmp::Ordering::Equal if *self == MarketKind::Moneyline => Some(MarketV2::DRAW),
+            std::cmp::Ordering::Equal => None,
+        })
+    }
 }
 
-// Simple linear bonding curve calculation for buying
-fn calculate_tokens_linear(
-    usdc_amount: u64,
-    current_supply: u64,
-    base_price: u64,
-    slope: u64,
-) -> Result<u64> {
-    // Linear bonding curve: price = base_price + (slope * supply / 1_000_000)
-    // We use the average price over the purchase range
-    
-    // Starting price at current supply
-    let start_price = base_price
-        .checked_add(
-            slope.checked_mul(current_supply / 1_000_000)
-                .ok_or(ErrorCode::MathOverflow)?
-        )
-        .ok_or(ErrorCode::MathOverflow)?;
-    
-    // This is simplified - for small purchases, we can approximate
-    // For production, you'd want to solve the quadratic equation properly
-    if start_price == 0 {
-        return Ok(0);
+// Progress of an optimistic proposal or a committee vote. Every other
+// resolution path and voiding wait while either is under way.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResolutionState {
+    Idle,
+    Proposed,   // Bonded proposal inside or past its dispute window
+    Disputed,   // Waiting for the arbiter
+    Committee,  // Assigned to a resolver committee, collecting votes
+    Contested,  // The committee can no longer agree; waiting for the admin
+}
+
+// A fixed set of resolvers for high-value markets, seeded by name. Any
+// `threshold` matching votes decide a market assigned to it. The threshold
+// must be a strict majority so two outcomes can never both reach it.
+#[account]
+pub struct ResolverCommittee {
+    pub name: String,  // MAX_NAME_LEN max
+    pub members: Vec<Pubkey>,  // MAX_MEMBERS max
+    pub threshold: u8,
+    pub bump: u8,
+}
+
+impl ResolverCommittee {
+    pub const MAX_NAME_LEN: usize = 32;
+    pub const MAX_MEMBERS: usize = 10;
+    const SPACE: usize = 4 + Self::MAX_NAME_LEN + 4 + 32 * Self::MAX_MEMBERS + 1 + 1;
+}
+
+// One committee's votes on one market, seeded by the market. `votes[i]` is
+// member i's outcome, kept after settlement as the record of who voted what.
+#[account]
+pub struct CommitteeBallot {
+    pub market: Pubkey,
+    pub committee: Pubkey,
+    pub votes: Vec<Option<u8>>,
+    pub bump: u8,
+}
+
+impl CommitteeBallot {
+    const SPACE: usize = 32 + 32 + 4 + 2 * ResolverCommittee::MAX_MEMBERS + 1;
+
+    // Matching votes per outcome
+    fn tally(&self) -> [u8; 3] {
+        let mut counts = [0u8; 3];
+        for outcome in self.votes.iter().flatten() {
+            counts[*outcome as usize] += 1;
+        }
+        counts
     }
-    
-    // Simple approximation: tokens = usdc_amount / start_price
-    // With scaling for 6 decimals
-    let tokens_out = (usdc_amount as u128)
-        .checked_mul(1_000_000)
-        .ok_or(ErrorCode::MathOverflow)?
-        .checked_div(start_price as u128)
-        .ok_or(ErrorCode::MathOverflow)? as u64;
-    
-    Ok(tokens_out)
-}
-
-// Calculate USDC returned when selling tokens
-fn calculate_usdc_linear(
-    token_amount: u64,
-    current_supply: u64,
-    base_price: u64,
-    slope: u64,
-) -> Result<u64> {
-    // Calculate average price over the sell range
-    // End supply after selling
-    let end_supply = current_supply
-        .checked_sub(token_amount)
-        .ok_or(ErrorCode::InsufficientSupply)?;
-    
-    // Average price = (start_price + end_price) / 2
-    let start_price = base_price
-        .checked_add(
-            slope.checked_mul(current_supply / 1_000_000)
-                .ok_or(ErrorCode::MathOverflow)?
-        )
-        .ok_or(ErrorCode::MathOverflow)?;
-    
-    let end_price = base_price
-        .checked_add(
-            slope.checked_mul(end_supply / 1_000_000)
-                .ok_or(ErrorCode::MathOverflow)?
-        )
-        .ok_or(ErrorCode::MathOverflow)?;
-    
-    let avg_price = (start_price + end_price) / 2;
-    
-    if avg_price == 0 {
-        return Ok(0);
+
+    fn decision(&self, threshold: u8) -> CommitteeDecision {
+        let counts = self.tally();
+        if let Some(outcome) = (0..3u8).find(|&o| counts[o as usize] >= threshold) {
+            return CommitteeDecision::Decided(outcome);
+        }
+        // Contested once no outcome can reach the threshold with the votes left
+        let remaining = self.votes.iter().filter(|v| v.is_none()).count() as u8;
+        let leader = counts.iter().copied().max().unwrap_or(0);
+        if leader + remaining < threshold {
+            CommitteeDecision::Contested
+        } else {
+            CommitteeDecision::Pending
+        }
     }
-    
-    // Calculate USDC out
-    let usdc_out = (token_amount as u128)
-        .checked_mul(avg_price as u128)
-        .ok_or(ErrorCode::MathOverflow)?
-        .checked_div(1_000_000) // Scale down for decimals
-        .ok_or(ErrorCode::MathOverflow)? as u64;
-    
-    Ok(usdc_out)
 }
 
-// Account structures
-#[derive(Accounts)]
-#[instruction(game_id: String)]
-pub struct CreateMarketV2<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + MarketV2::SPACE,
-        seeds = [b"market", game_id.as_bytes()],
-        bump
-    )]
-    pub market: Account<'info, MarketV2>,
-    
-    #[account(
-        init,
-        payer = authority,
-        mint::decimals = 6,
-        mint::authority = market,
-        seeds = [b"team_a_mint", game_id.as_bytes()],
-        bump
-    )]
-    pub team_a_mint: Account<'info, Mint>,
-    
-    #[account(
-        init,
-        payer = authority,
-        mint::decimals = 6,
-        mint::authority = market,
-        seeds = [b"team_b_mint", game_id.as_bytes()],
-        bump
-    )]
-    pub team_b_mint: Account<'info, Mint>,
-    
-    #[account(
-        init,
-        payer = authority,
-        token::mint = usdc_mint,
-        token::authority = market,
-        seeds = [b"usdc_vault", game_id.as_bytes()],
-        bump
-    )]
-    pub usdc_vault: Account<'info, TokenAccount>,
-    
-    pub usdc_mint: Account<'info, Mint>,
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
+#[derive(Debug, PartialEq, Eq)]
+enum CommitteeDecision {
+    Pending,
+    Decided(u8),
+    Contested,
+}
+
+// An optimistic proposal for one market, seeded by the market. Closed to the
+// proposer when the market settles.
+#[account]
+pub struct ResolutionProposal {
+    pub market: Pubkey,
+    pub proposer: Pubkey,
+    pub outcome: u8,  // 0 for team A, 1 for team B, 2 for a draw
+    pub bond: u64,    // Posted by the proposer and matched by a disputer
+    pub dispute_deadline: i64,
+    pub disputer: Option<Pubkey>,
+    pub bump: u8,
+}
+
+impl ResolutionProposal {
+    const SPACE: usize = 32 + 32 + 1 + 8 + 8 + 1 + 32 + 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameStatus {
+    Scheduled,
+    InProgress,
+    Final,
+    Cancelled,
+}
+
+// A publisher's result feed for one game, seeded by (publisher, game_id).
+// Team A is the home side.
+#[account]
+pub struct OracleResult {
+    pub game_id: String,  // 50 chars max
+    pub home_score: u16,
+    pub away_score: u16,
+    pub status: GameStatus,
+    pub publisher: Pubkey,
+    pub timestamp: i64,  // When the publisher last wrote the feed
+    pub bump: u8,
+}
+
+impl OracleResult {
+    const SPACE: usize = 4 + 50 + 2 + 2 + 1 + 32 + 8 + 1;
+
+    fn record(&mut self, home_score: u16, away_score: u16, status: GameStatus) -> Result<()> {
+        self.home_score = home_score;
+        self.away_score = away_score;
+        self.status = status;
+        self.timestamp = Clock::get()?.unix_timestamp;
+        Ok(())
+    }
+}
+
+#[account]
+pub struct MultiMarket {
+    pub authority: Pubkey,
+    pub game_id: String,  // 50 chars max
+    pub usdc_vault: Pubkey,  // Shared by every outcome
+    pub usdc_mint: Pubkey,
+    pub usdc_decimals: u8,
+    pub fee_vault: Pubkey,
+    pub protocol_fee_bps: u16,
+    pub creator_fee_bps: u16,
+    pub protocol_fees_accrued: u64,
+    pub creator_fees_accrued: u64,
+    pub base_price: u64,     // Every outcome's curve starts here
+    pub slope: u64,
+    pub pool_value: u64,     // Sum of the outcomes' pools
+    pub outcomes: Vec<Outcome>,  // MAX_OUTCOMES max; index is the outcome id
+    pub trading_halted: bool,
+    pub is_resolved: bool,
+    pub winner: Option<u8>,
+}
+
+impl MultiMarket {
+    pub const MIN_OUTCOMES: usize = 2;
+    pub const MAX_OUTCOMES: usize = 16;
+    const SPACE: usize = 32 + 4 + 50 + 32 + 32 + 1 + 32 + 2 + 2 + 8 + 8 + 8 + 8 + 8 + 4 + Outcome::SPACE * Self::MAX_OUTCOMES + 1 + 1 + 2;
+
+    // Every outcome is priced on the same linear curve
+    fn curve(&self) -> Box<dyn BondingCurve> {
+        CurveType::Linear.build(self.base_price, self.slope, 0)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct Outcome {
+    pub name: String,  // MAX_NAME_LEN chars max
+    pub mint: Pubkey,
+    pub supply: u64,
+    pub pool: u64,     // USDC contributed to the curve by this outcome's trades
+}
+
+impl Outcome {
+    pub const MAX_NAME_LEN: usize = 20;
+    const SPACE: usize = 4 + Self::MAX_NAME_LEN + 32 + 8 + 8;
+}
+
+#[account]
+pub struct ProtocolConfig {
+    pub admin: Pubkey,           // Manages roles, fees and the collateral allowlist
+    pub market_creator: Pubkey,  // Only key allowed to create markets
+    pub resolver: Pubkey,        // Only key allowed to resolve markets
+    pub pauser: Pubkey,          // Can pause the protocol and halt any market
+    pub paused: bool,
+    pub protocol_fee_bps: u16,   // Copied onto each new market
+    pub creator_fee_bps: u16,
+    pub treasury: Pubkey,        // Owner of protocol fee withdrawals
+    pub arbiter: Pubkey,         // Decides disputed proposals and keeps their bonds
+    pub resolution_bond: u64,    // USDC a proposal or dispute must post
+    pub dispute_window_secs: i64,
+    pub allowed_collateral_mints: Vec<Pubkey>,  // MAX_COLLATERAL_MINTS max
+    pub bump: u8,
+}
+
+impl ProtocolConfig {
+    pub const MAX_COLLATERAL_MINTS: usize = 8;
+    pub const MAX_TOTAL_FEE_BPS: u16 = 1_000;  // 10%
+    pub const DEFAULT_RESOLUTION_BOND: u64 = 100_000_000;  // 100 USDC
+    pub const DEFAULT_DISPUTE_WINDOW_SECS: i64 = 2 * 60 * 60;
+    const SPACE: usize = 32 + 32 + 32 + 32 + 1 + 2 + 2 + 32 + 32 + 8 + 8 + 4 + 32 * Self::MAX_COLLATERAL_MINTS + 1;
+}
+
+// Events
+//
+// Every event carries `version` so indexers can branch on layout changes, plus
+// the slot and unix timestamp it was emitted at. Layouts are still settling
+// before the first release, so the version stays at 1 until then and is bumped
+// on every layout change after it. Trade and claim events report the spot
+// price of the side they touched before and after; the other market events
+// snapshot both sides' spot prices. Prices are USDC base units per whole token.
+pub const EVENT_VERSION: u8 = 1;
+
+#[event]
+pub struct MarketCreatedEvent {
+    pub version: u8,
+    pub market: Pubkey,
+    pub authority: Pubkey,
+    pub game_id: String,
+    pub team_a: String,
+    pub team_b: String,
+    pub usdc_mint: Pubkey,
+    pub curve_type: CurveType,
+    pub kind: MarketKind,
+    pub base_price: u64,
+    pub slope: u64,
+    pub protocol_fee_bps: u16,
+    pub creator_fee_bps: u16,
+    pub trading_close_ts: i64,
+    pub oracle: Option<Pubkey>,
+    pub team_a_price: u64,
+    pub team_b_price: u64,
+    pub team_a_supply: u64,
+    pub team_b_supply: u64,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TradeEvent {
+    pub version: u8,
+    pub market: Pubkey,
+    pub trader: Pubkey,
+    pub action: TradeAction,
+    pub team: u8,
+    pub usdc_amount: u64,    // Paid in on buys (fees included), paid out on sells (fees excluded)
+    pub token_amount: u64,
+    pub protocol_fee: u64,
+    pub creator_fee: u64,
+    pub price_before: u64,
+    pub price_after: u64,
+    pub team_a_supply: u64,
+    pub team_b_supply: u64,
+    pub pool_value: u64,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TradeAction {
+    Buy,
+    Sell,
+}
+
+// Return data of `quote`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct TradeQuote {
+    pub team: u8,
+    pub action: TradeAction,
+    pub amount: u64,            // USDC in for a buy, tokens in for a sell (computed for exact-output quotes)
+    pub tokens_out: u64,        // Tokens minted to the buyer; 0 for a sell
+    pub usdc_out: u64,          // USDC to the seller after fees; 0 for a buy
+    pub average_price: u64,     // USDC per whole token, fees included
+    pub price_before: u64,
+    pub price_after: u64,
+    pub price_impact_bps: u64,  // |price_after - price_before| / price_before
+    pub fee: u64,               // protocol_fee + creator_fee
+    pub protocol_fee: u64,
+    pub creator_fee: u64,
+}
+
+#[event]
+pub struct CompleteSetEvent {
+    pub version: u8,
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub action: CompleteSetAction,
+    pub amount: u64,         // Sets minted or redeemed; one USDC base unit each
+    pub complete_sets: u64,  // Outstanding after this instruction
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub enum CompleteSetAction {
+    Mint,
+    Redeem,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResolutionAction {
+    Proposed,
+    Disputed,
+    Finalized,
+    Arbitrated,
+    Voided,
+}
+
+// One step of optimistic resolution. `actor` is the proposer, disputer or
+// arbiter, and `outcome` the arbiter's ruling for Arbitrated.
+#[event]
+pub struct ResolutionEvent {
+    pub version: u8,
+    pub market: Pubkey,
+    pub action: ResolutionAction,
+    pub actor: Pubkey,
+    pub proposer: Pubkey,
+    pub outcome: u8,
+    pub bond: u64,
+    pub dispute_deadline: i64,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+// `votes` is the whole ballot after this vote, in committee member order
+#[event]
+pub struct CommitteeVoteEvent {
+    pub version: u8,
+    pub market: Pubkey,
+    pub committee: Pubkey,
+    pub voter: Pubkey,
+    pub outcome: u8,
+    pub votes: Vec<Option<u8>>,
+    pub contested: bool,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OracleResultPublishedEvent {
+    pub version: u8,
+    pub oracle_result: Pubkey,
+    pub publisher: Pubkey,
+    pub game_id: String,
+    pub home_score: u16,
+    pub away_score: u16,
+    pub status: GameStatus,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TradingCloseExtendedEvent {
+    pub version: u8,
+    pub market: Pubkey,
+    pub authority: Pubkey,
+    pub previous_close_ts: i64,
+    pub trading_close_ts: i64,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TradingHaltedEvent {
+    pub version: u8,
+    pub market: Pubkey,
+    pub authority: Pubkey,
+    pub team_a_price: u64,
+    pub team_b_price: u64,
+    pub team_a_supply: u64,
+    pub team_b_supply: u64,
+    pub pool_value: u64,
+    pub slot: u64,
+    pub timestamp: i64,
 }
 
-#[derive(Accounts)]
-pub struct BuyOnCurve<'info> {
-    #[account(mut)]
-    pub buyer: Signer<'info>,
-    
-    #[account(
-        mut,
-        seeds = [b"market", market.game_id.as_bytes()],
-        bump
-    )]
-    pub market: Account<'info, MarketV2>,
-    
-    #[account(mut)]
-    pub team_a_mint: Account<'info, Mint>,
-    
-    #[account(mut)]
-    pub team_b_mint: Account<'info, Mint>,
-    
-    #[account(
-        mut,
-        associated_token::mint = team_a_mint,
-        associated_token::authority = buyer
-    )]
-    pub buyer_team_a_account: Account<'info, TokenAccount>,
-    
-    #[account(
-        mut,
-        associated_token::mint = team_b_mint,
-        associated_token::authority = buyer
-    )]
-    pub buyer_team_b_account: Account<'info, TokenAccount>,
-    
-    #[account(mut)]
-    pub buyer_usdc: Account<'info, TokenAccount>,
-    
-    #[account(mut)]
-    pub usdc_vault: Account<'info, TokenAccount>,
-    
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub system_program: Program<'info, System>,
+#[event]
+pub struct MarketResolvedEvent {
+    pub version: u8,
+    pub market: Pubkey,
+    pub resolver: Pubkey,
+    pub winner: u8,
+    pub scalar_value: Option<i64>,  // Reported value, for scalar markets
+    pub team_a_price: u64,
+    pub team_b_price: u64,
+    pub team_a_supply: u64,
+    pub team_b_supply: u64,
+    pub pool_value: u64,
+    pub slot: u64,
+    pub timestamp: i64,
 }
 
-#[derive(Accounts)]
-pub struct SellOnCurve<'info> {
-    #[account(mut)]
-    pub seller: Signer<'info>,
-    
-    #[account(
-        mut,
-        seeds = [b"market", market.game_id.as_bytes()],
-        bump
-    )]
-    pub market: Account<'info, MarketV2>,
-    
-    #[account(mut)]
-    pub team_a_mint: Account<'info, Mint>,
-    
-    #[account(mut)]
-    pub team_b_mint: Account<'info, Mint>,
-    
-    #[account(
-        mut,
-        associated_token::mint = team_a_mint,
-        associated_token::authority = seller
-    )]
-    pub seller_team_a_account: Account<'info, TokenAccount>,
-    
-    #[account(
-        mut,
-        associated_token::mint = team_b_mint,
-        associated_token::authority = seller
-    )]
-    pub seller_team_b_account: Account<'info, TokenAccount>,
-    
-    #[account(mut)]
-    pub seller_usdc: Account<'info, TokenAccount>,
-    
-    #[account(mut)]
-    pub usdc_vault: Account<'info, TokenAccount>,
-    
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
+#[event]
+pub struct WinningsClaimedEvent {
+    pub version: u8,
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub team: u8,
+    pub token_amount: u64,   // Winning tokens burned
+    pub payout: u64,
+    pub price_before: u64,
+    pub price_after: u64,
+    pub team_a_supply: u64,
+    pub team_b_supply: u64,
+    pub pool_value: u64,
+    pub slot: u64,
+    pub timestamp: i64,
 }
 
-// State
-#[account]
-pub struct MarketV2 {
+#[event]
+pub struct DrawClaimedEvent {
+    pub version: u8,
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub team_a_amount: u64,  // Team A tokens burned
+    pub team_b_amount: u64,  // Team B tokens burned
+    pub payout: u64,
+    pub team_a_supply: u64,
+    pub team_b_supply: u64,
+    pub pool_value: u64,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MarketVoidedEvent {
+    pub version: u8,
+    pub market: Pubkey,
     pub authority: Pubkey,
-    pub game_id: String,  // 50 chars max
-    pub team_a: String,   // 20 chars max
-    pub team_b: String,   // 20 chars max
-    pub team_a_mint: Pubkey,
-    pub team_b_mint: Pubkey,
-    pub usdc_vault: Pubkey,
-    pub base_price: u64,     // Base price (starting price)
-    pub slope: u64,          // Price increase per million tokens
+    pub team_a_supply: u64,
+    pub team_b_supply: u64,
+    pub team_a_pool: u64,
+    pub team_b_pool: u64,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RefundClaimedEvent {
+    pub version: u8,
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub team_a_amount: u64,  // Team A tokens burned
+    pub team_b_amount: u64,  // Team B tokens burned
+    pub refund: u64,
     pub team_a_supply: u64,
     pub team_b_supply: u64,
     pub pool_value: u64,
-    pub trading_halted: bool,
-    pub is_resolved: bool,
-    pub winner: Option<u8>,
+    pub slot: u64,
+    pub timestamp: i64,
 }
 
-impl MarketV2 {
-    const SPACE: usize = 32 + 4 + 50 + 4 + 20 + 4 + 20 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 1 + 1 + 1 + 1;
+#[event]
+pub struct FeesWithdrawnEvent {
+    pub version: u8,
+    pub market: Pubkey,
+    pub kind: FeeKind,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SubsidyWithdrawnEvent {
+    pub version: u8,
+    pub market: Pubkey,
+    pub destination: Pubkey,
+    pub subsidy: u64,  // Deposited at creation
+    pub amount: u64,   // Returned to the creator
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub enum FeeKind {
+    Protocol,
+    Creator,
+}
+
+// Full config snapshot, emitted by every config instruction
+#[event]
+pub struct MultiMarketCreatedEvent {
+    pub version: u8,
+    pub market: Pubkey,
+    pub authority: Pubkey,
+    pub game_id: String,
+    pub usdc_mint: Pubkey,
+    pub base_price: u64,
+    pub slope: u64,
+    pub protocol_fee_bps: u16,
+    pub creator_fee_bps: u16,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OutcomeAddedEvent {
+    pub version: u8,
+    pub market: Pubkey,
+    pub outcome: u8,
+    pub name: String,
+    pub mint: Pubkey,
+    pub price: u64,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OutcomeTradeEvent {
+    pub version: u8,
+    pub market: Pubkey,
+    pub trader: Pubkey,
+    pub action: TradeAction,
+    pub outcome: u8,
+    pub usdc_amount: u64,    // Same convention as TradeEvent
+    pub token_amount: u64,
+    pub protocol_fee: u64,
+    pub creator_fee: u64,
+    pub price_before: u64,
+    pub price_after: u64,
+    pub outcome_supply: u64,
+    pub pool_value: u64,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MultiMarketHaltedEvent {
+    pub version: u8,
+    pub market: Pubkey,
+    pub authority: Pubkey,
+    pub outcome_supplies: Vec<u64>,
+    pub pool_value: u64,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MultiMarketResolvedEvent {
+    pub version: u8,
+    pub market: Pubkey,
+    pub resolver: Pubkey,
+    pub winner: u8,
+    pub winner_supply: u64,
+    pub pool_value: u64,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OutcomeClaimedEvent {
+    pub version: u8,
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub outcome: u8,
+    pub token_amount: u64,
+    pub payout: u64,
+    pub outcome_supply: u64,
+    pub pool_value: u64,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ConfigUpdatedEvent {
+    pub version: u8,
+    pub admin: Pubkey,
+    pub market_creator: Pubkey,
+    pub resolver: Pubkey,
+    pub pauser: Pubkey,
+    pub treasury: Pubkey,
+    pub arbiter: Pubkey,
+    pub resolution_bond: u64,
+    pub dispute_window_secs: i64,
+    pub paused: bool,
+    pub protocol_fee_bps: u16,
+    pub creator_fee_bps: u16,
+    pub allowed_collateral_mints: Vec<Pubkey>,
+    pub slot: u64,
+    pub timestamp: i64,
 }
 
 // Errors
@@ -457,6 +4785,18 @@ impl MarketV2 {
 pub enum ErrorCode {
     #[msg("Trading is halted")]
     TradingHalted,
+    #[msg("Trading is not halted")]
+    TradingNotHalted,
+    #[msg("Already halted")]
+    AlreadyHalted,
+    #[msg("Market already resolved")]
+    AlreadyResolved,
+    #[msg("Market not resolved")]
+    MarketNotResolved,
+    #[msg("No winner set")]
+    NoWinner,
+    #[msg("No winning tokens")]
+    NoWinningTokens,
     #[msg("Invalid team selection")]
     InvalidTeam,
     #[msg("Slippage tolerance exceeded")]
@@ -467,4 +4807,464 @@ pub enum ErrorCode {
     InsufficientSupply,
     #[msg("Insufficient pool balance")]
     InsufficientPoolBalance,
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Collateral mint is not allowed")]
+    CollateralNotAllowed,
+    #[msg("Collateral mint is already allowed")]
+    CollateralAlreadyAllowed,
+    #[msg("Collateral allowlist is full")]
+    CollateralListFull,
+    #[msg("Protocol is paused")]
+    ProtocolPaused,
+    #[msg("Fee too high")]
+    FeeTooHigh,
+    #[msg("No fees to withdraw")]
+    NoFeesToWithdraw,
+    #[msg("Market is voided")]
+    MarketVoided,
+    #[msg("Market is not voided")]
+    MarketNotVoided,
+    #[msg("No tokens to refund")]
+    NoTokensToRefund,
+    #[msg("Invalid outcome index")]
+    InvalidOutcome,
+    #[msg("Market already has the maximum number of outcomes")]
+    TooManyOutcomes,
+    #[msg("Market needs at least two outcomes to trade")]
+    TooFewOutcomes,
+    #[msg("Outcomes cannot change after trading starts")]
+    OutcomesLocked,
+    #[msg("Name too long")]
+    NameTooLong,
+    #[msg("Invalid bonding curve parameters")]
+    InvalidCurveParams,
+    #[msg("LMSR markets need the creator's USDC account to pay the subsidy")]
+    SubsidyAccountRequired,
+    #[msg("No subsidy surplus to withdraw")]
+    NoSubsidyToWithdraw,
+    #[msg("Amount must be greater than zero")]
+    ZeroAmount,
+    #[msg("Not enough complete sets outstanding")]
+    InsufficientCompleteSets,
+    #[msg("Transaction deadline has passed")]
+    DeadlineExceeded,
+    #[msg("Trading closed at the market's close time")]
+    TradingClosed,
+    #[msg("Close time must be in the future and after the current one")]
+    InvalidCloseTime,
+    #[msg("Position belongs to a different market or wallet")]
+    InvalidPosition,
+    #[msg("Oracle result is not from this market's oracle")]
+    InvalidOracle,
+    #[msg("Oracle result is not final")]
+    OracleResultNotFinal,
+    #[msg("Final oracle results cannot be changed")]
+    OracleResultFinal,
+    #[msg("Resolution bond and dispute window must be positive")]
+    InvalidResolutionParams,
+    #[msg("A resolution proposal is pending")]
+    ProposalPending,
+    #[msg("No undisputed proposal is pending")]
+    NoProposal,
+    #[msg("Proposal is not disputed")]
+    NotDisputed,
+    #[msg("Dispute window has closed")]
+    DisputeWindowClosed,
+    #[msg("Dispute window is still open")]
+    DisputeWindowOpen,
+    #[msg("Market is being resolved by its committee")]
+    CommitteeResolution,
+    #[msg("Committee needs distinct members and a majority threshold")]
+    InvalidCommittee,
+    #[msg("Market is not collecting committee votes")]
+    NotCommitteeVoting,
+    #[msg("Signer is not a member of the committee")]
+    NotCommitteeMember,
+    #[msg("Member has already voted")]
+    AlreadyVoted,
+    #[msg("Market is not contested")]
+    NotContested,
+    #[msg("Invalid market kind for this operation")]
+    InvalidMarketKind,
+    #[msg("Winning outcome has no holders")]
+    WinnerHasNoHolders,
+    #[msg("Complete sets are only available on LMSR markets")]
+    CompleteSetsRequireLmsr,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fees_round_down_and_stay_within_amount() {
+        assert_eq!(calculate_fees(10_000, 50, 25).unwrap(), (50, 25));
+        assert_eq!(calculate_fees(199, 50, 50).unwrap(), (0, 0));
+        let (protocol, creator) = calculate_fees(u64::MAX, ProtocolConfig::MAX_TOTAL_FEE_BPS, 0).unwrap();
+        assert_eq!(protocol, u64::MAX / 10);
+        assert_eq!(creator, 0);
+    }
+
+    #[test]
+    fn refunds_never_exceed_vault() {
+        // LMSR sides share one pool, so only the per-side curves apply
+        for (curve_type, base_price, slope) in all_curves().into_iter().filter(|(c, ..)| !matches!(c, CurveType::Lmsr { .. })) {
+            let curve = curve_type.build(base_price, slope, 0);
+            // (team, usdc in) buys followed by a partial sell on each side
+            let buys = [(0u8, 12_345_678u64), (1, 3), (0, 999), (1, 40_000_000), (0, 1), (1, 7_777_777)];
+            let mut supply = [0u64; 2];
+            let mut side_pool = [0u64; 2];
+            let mut holdings = Vec::new();
+            for (team, usdc) in buys {
+                let t = team as usize;
+                let tokens = curve.tokens_for_usdc(supply[t], usdc).unwrap();
+                supply[t] += tokens;
+                side_pool[t] += usdc;
+                holdings.push((t, tokens));
+            }
+            for t in 0..2 {
+                let (_, held) = holdings.iter_mut().find(|(side, _)| *side == t).unwrap();
+                let amount = *held / 2;
+                let usdc_out = curve.sell_proceeds(supply[t], amount).unwrap();
+                assert!(usdc_out <= side_pool[t]);
+                *held -= amount;
+                supply[t] -= amount;
+                side_pool[t] -= usdc_out;
+            }
+
+            let mut vault = side_pool[0] + side_pool[1];
+            // Refund in an order unrelated to the buy order
+            holdings.reverse();
+            holdings.rotate_left(2);
+            for (t, balance) in holdings {
+                let refund = pro_rata_share(balance, side_pool[t], supply[t]).unwrap();
+                assert!(refund <= vault);
+                vault -= refund;
+                side_pool[t] -= refund;
+                supply[t] -= balance;
+            }
+            assert_eq!(supply, [0, 0]);
+            // The last holder on each side takes whatever is left of that side
+            assert_eq!(side_pool, [0, 0]);
+            assert_eq!(vault, 0);
+        }
+    }
+
+    #[test]
+    fn settlement_pools_cover_pool_exactly() {
+        for pool in [0u64, 1, 2, 999_999, u64::MAX] {
+            for winner in [0u8, 1, MarketV2::DRAW] {
+                for (supply_a, supply_b) in [(0u64, 0u64), (5, 0), (0, 5), (3, 7)] {
+                    let (a, b) = settlement_pools(MarketV2::winner_share(winner), pool, supply_a, supply_b);
+                    assert_eq!(a as u128 + b as u128, pool as u128);
+                }
+            }
+        }
+        assert_eq!(settlement_pools(MarketV2::winner_share(MarketV2::DRAW), 11, 4, 9), (5, 6));
+        assert_eq!(settlement_pools(MarketV2::winner_share(MarketV2::DRAW), 11, 0, 9), (0, 11));
+        assert_eq!(settlement_pools(MarketV2::winner_share(MarketV2::DRAW), 11, 4, 0), (11, 0));
+    }
+
+    fn all_curves() -> Vec<(CurveType, u64, u64)> {
+        vec![
+            (CurveType::Linear, 100_000, 10_000),
+            (CurveType::Constant, 500_000, 0),
+            (CurveType::Power { exponent: 100 }, 100_000, 10_000),
+            (CurveType::Power { exponent: 150 }, 50_000, 300),
+            (CurveType::Power { exponent: 250 }, 50_000, 3),
+            (CurveType::Power { exponent: 400 }, 1, 1),
+            (CurveType::Sigmoid { midpoint: 50_000_000, width: 10_000_000 }, 100_000, 900_000),
+            (CurveType::Sigmoid { midpoint: 0, width: 1 }, 0, 1_000_000),
+            (CurveType::Lmsr { liquidity: 100_000_000 }, 0, 0),
+        ]
+    }
+
+    #[test]
+    fn curve_params_are_validated() {
+        assert!(CurveType::Linear.validate(0, 0).is_err());
+        assert!(CurveType::Linear.validate(0, 1).is_ok());
+        assert!(CurveType::Power { exponent: 0 }.validate(1, 1).is_err());
+        assert!(CurveType::Power { exponent: 401 }.validate(1, 1).is_err());
+        assert!(CurveType::Power { exponent: 150 }.validate(0, 0).is_err());
+        assert!(CurveType::Sigmoid { midpoint: 1, width: 0 }.validate(1, 1).is_err());
+        assert!(CurveType::Sigmoid { midpoint: 1, width: 1 }.validate(0, 0).is_err());
+        assert!(CurveType::Constant.validate(0, 0).is_err());
+        assert!(CurveType::Lmsr { liquidity: 999_999 }.validate(0, 0).is_err());
+        for (curve_type, base_price, slope) in all_curves() {
+            assert!(curve_type.validate(base_price, slope).is_ok());
+        }
+    }
+
+    #[test]
+    fn curves_price_rises_with_supply() {
+        for (curve_type, base_price, slope) in all_curves() {
+            let curve = curve_type.build(base_price, slope, 0);
+            let mut last = curve.spot_price(0).unwrap();
+            assert!(last >= base_price);
+            for supply in [1u64, 1_000_000, 40_000_000, 50_000_000, 60_000_000, 1_000_000_000] {
+                let price = curve.spot_price(supply).unwrap();
+                assert!(price >= last, "{:?} at {}", curve_type, supply);
+                last = price;
+            }
+        }
+    }
+
+    #[test]
+    fn curves_buy_the_most_tokens_the_payment_covers() {
+        for (curve_type, base_price, slope) in all_curves() {
+            let curve = curve_type.build(base_price, slope, 0);
+            for supply in [0u64, 999_999, 49_000_000, 123_456_789] {
+                for usdc in [1u64, 1_000, 10_000_000, 5_000_000_000] {
+                    let tokens = curve.tokens_for_usdc(supply, usdc).unwrap();
+                    assert!(curve.buy_cost(supply, tokens).unwrap() <= usdc);
+                    assert!(curve.buy_cost(supply, tokens + 1).is_ok_and(|c| c > usdc));
+                }
+            }
+        }
+    }
+
+    fn test_market(curve_type: CurveType, base_price: u64, slope: u64) -> MarketV2 {
+        MarketV2 {
+            authority: Pubkey::default(),
+            game_id: "TEST".to_string(),
+            team_a: "A".to_string(),
+            team_b: "B".to_string(),
+            team_a_mint: Pubkey::default(),
+            team_b_mint: Pubkey::default(),
+            usdc_vault: Pubkey::default(),
+            usdc_mint: Pubkey::default(),
+            usdc_decimals: 6,
+            fee_vault: Pubkey::default(),
+            protocol_fee_bps: 50,
+            creator_fee_bps: 25,
+            protocol_fees_accrued: 0,
+            creator_fees_accrued: 0,
+            curve_type,
+            kind: MarketKind::Moneyline,
+            base_price,
+            slope,
+            team_a_supply: 0,
+            team_b_supply: 0,
+            pool_value: 0,
+            team_a_pool: 0,
+            team_b_pool: 0,
+            subsidy: 0,
+            subsidy_surplus: 0,
+            complete_sets: 0,
+            trading_close_ts: i64::MAX,
+            oracle: None,
+            resolution_state: ResolutionState::Idle,
+            scalar_value: None,
+            trading_halted: false,
+            is_resolved: false,
+            is_voided: false,
+            winner: None,
+        }
+    }
+
+    #[test]
+    fn quote_matches_curve_math() {
+        let mut market = test_market(CurveType::Linear, 100_000, 10_000);
+        let buy = quote_trade(&market, 0, TradeAction::Buy, 10_000_000, false, 0).unwrap();
+        let curve = market.curve(0);
+        assert_eq!((buy.protocol_fee, buy.creator_fee, buy.fee), (50_000, 25_000, 75_000));
+        assert_eq!(buy.tokens_out, curve.tokens_for_usdc(0, 9_925_000).unwrap());
+        assert_eq!(buy.average_price, (10_000_000u128 * TOKEN_SCALE / buy.tokens_out as u128) as u64);
+        assert_eq!(buy.price_before, 100_000);
+        assert_eq!(buy.price_after, curve.spot_price(buy.tokens_out).unwrap());
+        assert_eq!(
+            buy.price_impact_bps,
+            (buy.price_after - 100_000) * BPS_DENOMINATOR as u64 / 100_000
+        );
+
+        market.team_a_supply = buy.tokens_out;
+        market.team_a_pool = 9_925_000;
+        market.pool_value = 9_925_000;
+        let sell = quote_trade(&market, 0, TradeAction::Sell, buy.tokens_out, false, 0).unwrap();
+        let proceeds = curve.sell_proceeds(buy.tokens_out, buy.tokens_out).unwrap();
+        assert_eq!(sell.usdc_out + sell.fee, proceeds);
+        assert_eq!((sell.tokens_out, sell.price_after), (0, 100_000));
+
+        assert!(quote_trade(&market, 0, TradeAction::Sell, buy.tokens_out + 1, false, 0).is_err());
+        assert!(quote_trade(&market, 2, TradeAction::Buy, 1, false, 0).is_err());
+        market.trading_halted = true;
+        assert!(quote_trade(&market, 0, TradeAction::Buy, 1, false, 0).is_err());
+    }
+
+    #[test]
+    fn trading_closes_at_kickoff() {
+        let mut market = test_market(CurveType::Linear, 100_000, 10_000);
+        market.trading_close_ts = 1_700_000_000;
+        assert!(quote_trade(&market, 0, TradeAction::Buy, 1_000_000, false, 1_699_999_999).is_ok());
+        assert!(!market.is_trading_closed(1_699_999_999));
+        for now in [1_700_000_000, 1_800_000_000] {
+            assert!(quote_trade(&market, 0, TradeAction::Buy, 1_000_000, false, now).is_err());
+            assert!(market.is_trading_closed(now));
+        }
+    }
+
+    #[test]
+    fn position_tracks_average_cost() {
+        let mut position = UserPosition {
+            market: Pubkey::default(),
+            user: Pubkey::default(),
+            team_a_tokens: 0,
+            team_b_tokens: 0,
+            team_a_cost_basis: 0,
+            team_b_cost_basis: 0,
+            usdc_spent: 0,
+            usdc_received: 0,
+            realized_pnl: 0,
+            first_trade_slot: 0,
+            last_trade_slot: 0,
+            bump: 0,
+        };
+        position.record_buy(0, 10_000_000, 40_000_000, 7).unwrap();
+        position.record_buy(0, 20_000_000, 40_000_000, 9).unwrap();
+        position.record_buy(1, 5_000_000, 10_000_000, 9).unwrap();
+
+        // A quarter of the A tokens releases a quarter of their 30 USDC basis
+        position.record_sell(0, 20_000_000, 9_000_000, 12).unwrap();
+        assert_eq!((position.team_a_tokens, position.team_a_cost_basis), (60_000_000, 22_500_000));
+        assert_eq!(position.realized_pnl, 1_500_000);
+
+        // Selling more than was recorded releases the whole basis
+        position.record_sell(1, 15_000_000, 4_000_000, 15).unwrap();
+        assert_eq!((position.team_b_tokens, position.team_b_cost_basis), (0, 0));
+        assert_eq!(position.realized_pnl, 500_000);
+
+        assert_eq!((position.usdc_spent, position.usdc_received), (35_000_000, 13_000_000));
+        assert_eq!((position.first_trade_slot, position.last_trade_slot), (7, 15));
+    }
+
+    #[test]
+    fn spread_and_total_outcomes_use_half_point_lines() {
+        // Team A -3.5: a 4-point home win covers, a 3-point one does not
+        let spread = MarketKind::Spread { line: -7 };
+        assert_eq!(spread.outcome(24, 20).unwrap(), Some(0));
+        assert_eq!(spread.outcome(23, 20).unwrap(), Some(1));
+        assert_eq!(spread.outcome(20, 24).unwrap(), Some(1));
+
+        // Team A +3: a 3-point loss pushes
+        let spread = MarketKind::Spread { line: 6 };
+        assert_eq!(spread.outcome(17, 20).unwrap(), None);
+        assert_eq!(spread.outcome(18, 20).unwrap(), Some(0));
+
+        // Over/under 47.5 never pushes; 47 pushes on 47 points
+        let total = MarketKind::Total { line: 95 };
+        assert_eq!(total.outcome(28, 20).unwrap(), Some(0));
+        assert_eq!(total.outcome(27, 20).unwrap(), Some(1));
+        let total = MarketKind::Total { line: 94 };
+        assert_eq!(total.outcome(27, 20).unwrap(), None);
+        assert_eq!(total.outcome(u16::MAX, u16::MAX).unwrap(), Some(0));
+
+        assert_eq!(MarketKind::Moneyline.outcome(10, 10).unwrap(), Some(MarketV2::DRAW));
+    }
+
+    #[test]
+    fn only_moneyline_markets_settle_as_draws() {
+        let mut market = test_market(CurveType::Linear, 100_000, 10_000);
+        assert!(market.check_outcome(MarketV2::DRAW).is_ok());
+        assert!(market.check_outcome(MarketV2::DRAW + 1).is_err());
+
+        market.kind = MarketKind::Spread { line: 6 };
+        assert!(market.check_outcome(1).is_ok());
+        assert!(market.check_outcome(MarketV2::DRAW).is_err());
+        market.kind = MarketKind::Total { line: 94 };
+        assert!(market.check_outcome(MarketV2::DRAW).is_err());
+
+        market.kind = MarketKind::Scalar { lower: 200, upper: 300 };
+        for outcome in [0, 1, MarketV2::DRAW] {
+            assert!(market.check_outcome(outcome).is_err());
+        }
+    }
+
+    #[test]
+    fn scalar_shares_split_the_pool_linearly() {
+        let kind = MarketKind::Scalar { lower: 200, upper: 300 };
+        assert!(kind.validate().is_ok());
+        assert!(MarketKind::Scalar { lower: 300, upper: 300 }.validate().is_err());
+        assert!(kind.outcome(24, 20).is_err());
+
+        // 275 yards on a 200-300 range: long is owed three quarters
+        assert_eq!(settlement_pools((75, 100), 1_000, 5, 5), (750, 250));
+        assert_eq!(settlement_pools((1, 3), 10, 5, 5), (3, 7));
+        assert_eq!(settlement_pools((75, 100), 1_000, 0, 5), (0, 1_000));
+        assert_eq!(settlement_pools((100, 100), 1_000, 0, 5), (1_000, 0));
+
+        let mut market = test_market(CurveType::Lmsr { liquidity: 100_000_000 }, 0, 0);
+        market.team_a_supply = 40_000_000;
+        market.team_b_supply = 20_000_000;
+        market.pool_value = 100_000_000;
+        let (a, b) = lmsr::settlement_pools(&market, Some((75, 100))).unwrap();
+        assert_eq!((a, b), (30_000_000, 5_000_000));
+        assert_eq!(
+            lmsr::settlement_pools(&market, Some(MarketV2::winner_share(MarketV2::DRAW))).unwrap(),
+            (20_000_000, 10_000_000)
+        );
+    }
+
+    #[test]
+    fn committee_decides_on_threshold_or_deadlock() {
+        let ballot = |votes: &[Option<u8>]| CommitteeBallot {
+            market: Pubkey::default(),
+            committee: Pubkey::default(),
+            votes: votes.to_vec(),
+            bump: 0,
+        };
+
+        // 3 of 5
+        assert_eq!(ballot(&[Some(0), Some(1), None, None, None]).decision(3), CommitteeDecision::Pending);
+        assert_eq!(ballot(&[Some(0), Some(1), Some(0), None, Some(0)]).decision(3), CommitteeDecision::Decided(0));
+        assert_eq!(ballot(&[Some(2), Some(2), Some(1), Some(2), None]).decision(3), CommitteeDecision::Decided(2));
+        // Two votes left can still lift either side to 3
+        assert_eq!(ballot(&[Some(0), Some(1), Some(2), None, None]).decision(3), CommitteeDecision::Pending);
+        assert_eq!(ballot(&[Some(0), Some(1), Some(2), Some(1), None]).decision(3), CommitteeDecision::Pending);
+        assert_eq!(ballot(&[Some(0), Some(1), Some(2), Some(1), Some(0)]).decision(3), CommitteeDecision::Contested);
+        // 2 of 3
+        assert_eq!(ballot(&[Some(0), Some(1), Some(2)]).decision(2), CommitteeDecision::Contested);
+    }
+
+    #[test]
+    fn gross_for_net_is_minimal_cover() {
+        for (protocol_bps, creator_bps) in [(0u16, 0u16), (50, 25), (1, 999), (333, 333)] {
+            for net in [0u64, 1, 99, 10_000, 123_456_789, u64::MAX / 2] {
+                let gross = gross_for_net(net, protocol_bps, creator_bps).unwrap();
+                let (p, c) = calculate_fees(gross, protocol_bps, creator_bps).unwrap();
+                assert!(gross - p - c >= net);
+                if gross > net {
+                    let (p, c) = calculate_fees(gross - 1, protocol_bps, creator_bps).unwrap();
+                    assert!(gross - 1 - p - c < net);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn exact_output_quotes_cover_the_curve() {
+        for (curve_type, base_price, slope) in all_curves() {
+            let mut market = test_market(curve_type, base_price, slope);
+            let buy = quote_trade(&market, 0, TradeAction::Buy, 3_000, true, 0).unwrap();
+            let curve = market.curve(0);
+            assert_eq!(buy.tokens_out, 3_000);
+            assert!(buy.amount - buy.fee >= curve.buy_cost(0, 3_000).unwrap(), "{:?}", curve_type);
+
+            let filled = quote_trade(&market, 0, TradeAction::Buy, 500_000_000, false, 0).unwrap();
+            market.team_a_supply = filled.tokens_out;
+            market.team_a_pool = filled.amount - filled.fee;
+            market.pool_value = market.team_a_pool;
+            let curve = market.curve(0);
+            for usdc in [1u64, 777, 25_000_000] {
+                let sell = quote_trade(&market, 0, TradeAction::Sell, usdc, true, 0).unwrap();
+                assert_eq!(sell.usdc_out, usdc);
+                let proceeds = curve.sell_proceeds(market.team_a_supply, sell.amount).unwrap();
+                assert!(proceeds >= usdc + sell.fee, "{:?}", curve_type);
+                // One token fewer would not have covered it
+                let short = curve.sell_proceeds(market.team_a_supply, sell.amount - 1).unwrap();
+                assert!(short < gross_for_net(usdc, 50, 25).unwrap(), "{:?}", curve_type);
+            }
+            assert!(quote_trade(&market, 0, TradeAction::Sell, market.pool_value, true, 0).is_err());
+        }
+    }
 }
@@ -91,12 +91,16 @@ pub mod sportsxchange {
         ctx: Context<Swap>,
         amount_in: u64,
         minimum_amount_out: u64,
+        deadline_unix_ts: Option<i64>,
     ) -> Result<()> {
         let market = &ctx.accounts.market;
         let pool = &ctx.accounts.pool;
 
         // Validations
         require!(amount_in > 0, ErrorCode::InvalidAmount);
+        if let Some(deadline) = deadline_unix_ts {
+            require!(Clock::get()?.unix_timestamp <= deadline, ErrorCode::DeadlineExceeded);
+        }
         require!(market.is_active, ErrorCode::MarketNotActive);
 
         // Calculate amount out
@@ -173,12 +177,16 @@ pub mod sportsxchange {
         ctx: Context<Swap>,
         amount_in: u64,
         minimum_amount_out: u64,
+        deadline_unix_ts: Option<i64>,
     ) -> Result<()> {
         let market = &ctx.accounts.market;
         let pool = &ctx.accounts.pool;
 
         // Validations
         require!(amount_in > 0, ErrorCode::InvalidAmount);
+        if let Some(deadline) = deadline_unix_ts {
+            require!(Clock::get()?.unix_timestamp <= deadline, ErrorCode::DeadlineExceeded);
+        }
         require!(market.is_active, ErrorCode::MarketNotActive);
 
         let amount_out = calculate_amount_out(
@@ -604,4 +612,6 @@ pub enum ErrorCode {
     MarketAlreadyResolved,
     #[msg("Invalid amount: must be greater than zero")]
     InvalidAmount,
+    #[msg("Transaction deadline has passed")]
+    DeadlineExceeded,
 }
@@ -0,0 +1,353 @@
+// Logarithmic Market Scoring Rule for two-sided markets
+//
+// Both sides are priced off one cost function of the two supplies
+//
+//   C(qa, qb) = b * ln(e^(qa / b) + e^(qb / b))
+//
+// with the liquidity parameter b and the supplies in base units; a winning
+// token base unit pays one USDC base unit. Buying `t` of side A costs
+// C(qa + t, qb) - C(qa, qb) and A's price is
+//
+//   p_a = e^(qa / b) / (e^(qa / b) + e^(qb / b)) = 1 / (1 + e^((qb - qa) / b))
+//
+// so p_a + p_b = 1 and buying either side moves both prices.
+//
+// The creator pays C(0, 0) = b * ln 2 into the vault at creation. Since
+// C(qa, qb) >= max(qa, qb) the vault always covers the winning side at one
+// USDC per token, so the creator's loss is bounded by that subsidy.
+//
+// With d = (qb - qa) / b and softplus(x) = ln(1 + e^x) the cost of a buy is
+//
+//   C(qa + t, qb) - C(qa, qb) = t - b * (softplus(d) - softplus(d - t / b))
+//
+// and softplus only ever calls exp on non-positive arguments, so nothing
+// overflows however far apart the supplies drift. softplus is accurate to
+// 2^-54 in fixed point (see sportsxchange_math::fixed_point); buys round
+// the cost up and sells round it down after widening the softplus
+// difference by 2^-48.
+
+use anchor_lang::prelude::*;
+
+use sportsxchange_math::fixed_point::{exp, ln, mul, to_integer, LN_2, ONE};
+use sportsxchange_math::TOKEN_SCALE;
+
+use crate::{BondingCurve, ErrorCode, MarketV2};
+
+// Smallest liquidity parameter accepted at creation: 1 USDC. It keeps
+// supply / b within the range of Q64.64.
+pub const MIN_LIQUIDITY: u64 = 1_000_000;
+
+// Widening applied to every softplus difference
+const MARGIN: u128 = ONE >> 48;
+
+// ln(1 + e^-z) for z >= 0, in (0, ln 2]
+fn ln_1p_exp_neg(z: u128) -> u128 {
+    // e^-64 is below one ulp
+    if z >= 64 * ONE {
+        return 0;
+    }
+    let e = exp(-(z as i128)).unwrap_or(0);
+    ln(ONE + e) as u128
+}
+
+// ln(1 + e^x) = max(x, 0) + ln(1 + e^-|x|)
+fn softplus(x: i128) -> i128 {
+    x.max(0) + ln_1p_exp_neg(x.unsigned_abs()) as i128
+}
+
+// numerator / b in signed Q64.64, rounded toward zero
+fn ratio(numerator: i128, b: u64) -> i128 {
+    let magnitude = numerator.unsigned_abs();
+    let b = b as u128;
+    let q = ((magnitude / b) << 64) + ((magnitude % b) << 64) / b;
+    if numerator < 0 {
+        -(q as i128)
+    } else {
+        q as i128
+    }
+}
+
+// b * x for Q64.64 x >= 0, in base units
+fn scale(b: u64, x: u128, round_up: bool) -> Result<u128> {
+    let product = mul((b as u128) << 64, x).ok_or(ErrorCode::MathOverflow)?;
+    Ok(to_integer(product, round_up))
+}
+
+// C(0, 0) = b * ln 2, rounded up: what the creator deposits
+pub fn subsidy(liquidity: u64) -> Result<u64> {
+    let cost = scale(liquidity, LN_2 + 1, true)?;
+    u64::try_from(cost).map_err(|_| ErrorCode::MathOverflow.into())
+}
+
+// What each side is owed once the outcome is known: with team A owed `share`
+// (numerator, denominator), a team A token pays that fraction of one USDC and
+// a team B token the rest, so a winning token pays one USDC and a drawn one
+// half. A voided market pays every token its last price. Whatever the pool
+// holds beyond that is the creator's subsidy surplus.
+pub fn settlement_pools(market: &MarketV2, share: Option<(u64, u64)>) -> Result<(u64, u64)> {
+    let (a_supply, b_supply) = (market.team_a_supply, market.team_b_supply);
+    let (a_pool, b_pool) = match share {
+        Some((num, den)) => {
+            let part = |supply: u64, num: u64| (supply as u128 * num as u128 / den as u128) as u64;
+            (part(a_supply, num), part(b_supply, den - num))
+        }
+        None => {
+            let value = |team: u8, supply: u64| -> Result<u64> {
+                let price = market.curve(team).spot_price(supply)?;
+                Ok((supply as u128 * price as u128 / TOKEN_SCALE) as u64)
+            };
+            (value(0, a_supply)?, value(1, b_supply)?)
+        }
+    };
+
+    // C(qa, qb) covers both in exact arithmetic; the clamp only guards rounding
+    let a_pool = a_pool.min(market.pool_value);
+    Ok((a_pool, b_pool.min(market.pool_value - a_pool)))
+}
+
+// One side of an LMSR market, with the other side's supply held fixed
+pub struct LmsrCurve {
+    pub liquidity: u64,
+    pub other_supply: u64,
+}
+
+impl LmsrCurve {
+    // Upper (round_up) or lower bound on C(s1, other) - C(s0, other) in USDC
+    // base units
+    fn cost(&self, s0: u64, s1: u64, round_up: bool) -> Result<u64> {
+        if s0 == s1 {
+            return Ok(0);
+        }
+
+        let other = self.other_supply as i128;
+        let d0 = ratio(other - s0 as i128, self.liquidity);
+        let d1 = ratio(other - s1 as i128, self.liquidity);
+        let drop = (softplus(d0) - softplus(d1)).max(0) as u128;
+
+        // A smaller discount makes a larger cost
+        let discount = if round_up {
+            scale(self.liquidity, drop.saturating_sub(MARGIN), false)?
+        } else {
+            scale(self.liquidity, drop + MARGIN, true)?
+        };
+        let discount = u64::try_from(discount).unwrap_or(u64::MAX);
+
+        Ok((s1 - s0).saturating_sub(discount))
+    }
+}
+
+impl BondingCurve for LmsrCurve {
+    fn spot_price(&self, supply: u64) -> Result<u64> {
+        // 1 / (1 + e^d) with the exponential kept at or below one
+        let d = ratio(self.other_supply as i128 - supply as i128, self.liquidity);
+        let e = exp(-(d.unsigned_abs() as i128)).unwrap_or(0);
+        let numerator = if d >= 0 { e } else { ONE };
+        let price = TOKEN_SCALE * numerator / (ONE + e);
+        Ok(price as u64)
+    }
+
+    fn buy_cost(&self, supply: u64, amount: u64) -> Result<u64> {
+        let end = supply.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+        self.cost(supply, end, true)
+    }
+
+    fn sell_proceeds(&self, supply: u64, amount: u64) -> Result<u64> {
+        let start = supply.checked_sub(amount).ok_or(ErrorCode::InsufficientSupply)?;
+        self.cost(start, supply, false)
+    }
+
+    // Inverting C gives t = b * u with
+    //
+    //   u = ln(e^m + e^d * (e^m - 1)),  m = usdc / b,  d = (other - supply) / b
+    //
+    // evaluated as a log-sum-exp. The fixed-point estimate is within a few
+    // base units, so a short gallop and bisection against buy_cost finish it.
+    fn tokens_for_usdc(&self, supply: u64, usdc_amount: u64) -> Result<u64> {
+        if usdc_amount == 0 {
+            return Ok(0);
+        }
+
+        let m = ratio(usdc_amount as i128, self.liquidity);
+        let d = ratio(self.other_supply as i128 - supply as i128, self.liquidity);
+
+        // ln(e^m - 1) = m + ln(1 - e^-m)
+        let below_one = (ONE - exp(-m).unwrap_or(0)).max(1);
+        let x = d + m + ln(below_one);
+        let u = x.max(m) + ln_1p_exp_neg((x - m).unsigned_abs()) as i128;
+
+        let max_amount = u64::MAX - supply;
+        let estimate = u64::try_from(scale(self.liquidity, u.max(0) as u128, false)?)
+            .unwrap_or(u64::MAX)
+            .min(max_amount);
+
+        let affordable = |amount: u64| {
+            self.buy_cost(supply, amount).is_ok_and(|cost| cost <= usdc_amount)
+        };
+
+        // Bracket the answer: lo affordable, hi not (or past the cap)
+        let (mut lo, mut hi);
+        let mut step = 1u64;
+        if affordable(estimate) {
+            lo = estimate;
+            loop {
+                let next = lo.saturating_add(step).min(max_amount);
+                if next == lo {
+                    return Ok(lo);
+                }
+                if !affordable(next) {
+                    hi = next;
+                    break;
+                }
+                lo = next;
+                step = step.saturating_mul(2);
+            }
+        } else {
+            hi = estimate;
+            loop {
+                // Buying nothing is always affordable, so this ends
+                let next = hi.saturating_sub(step);
+                if affordable(next) {
+                    lo = next;
+                    break;
+                }
+                hi = next;
+                step = step.saturating_mul(2);
+            }
+        }
+
+        while hi - lo > 1 {
+            let mid = lo + (hi - lo) / 2;
+            if affordable(mid) {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+
+        Ok(lo)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sportsxchange_math::testing::{to_f64, Rng};
+
+    fn softplus_f64(x: f64) -> f64 {
+        x.max(0.0) + (-x.abs()).exp().ln_1p()
+    }
+
+    fn cost_f64(b: f64, qa: f64, qb: f64) -> f64 {
+        let m = qa.max(qb);
+        m + b * (((qa - m) / b).exp() + ((qb - m) / b).exp()).ln()
+    }
+
+    fn random_curve(rng: &mut Rng) -> LmsrCurve {
+        LmsrCurve {
+            liquidity: MIN_LIQUIDITY + rng.below(10_000_000_000),
+            other_supply: rng.below(50_000_000_000),
+        }
+    }
+
+    #[test]
+    fn softplus_matches_f64() {
+        let mut rng = Rng(0x50f7);
+        for _ in 0..2_000 {
+            let x = (rng.below(200 << 20) as i128 - (100 << 20)) << 44;
+            let expected = softplus_f64(x as f64 / ONE as f64);
+            let actual = softplus(x) as f64 / ONE as f64;
+            assert!((actual - expected).abs() < 1e-13 * expected.max(1.0), "softplus {} vs {}", actual, expected);
+        }
+        assert_eq!(ln_1p_exp_neg(0), LN_2);
+    }
+
+    #[test]
+    fn prices_sum_to_one() {
+        let mut rng = Rng(0x9a1e);
+        for _ in 0..1_000 {
+            let b = MIN_LIQUIDITY + rng.below(10_000_000_000);
+            let (qa, qb) = (rng.below(50_000_000_000), rng.below(50_000_000_000));
+            let pa = LmsrCurve { liquidity: b, other_supply: qb }.spot_price(qa).unwrap();
+            let pb = LmsrCurve { liquidity: b, other_supply: qa }.spot_price(qb).unwrap();
+            assert!(pa + pb <= TOKEN_SCALE as u64 && pa + pb >= TOKEN_SCALE as u64 - 2);
+
+            let expected = 1e6 / (1.0 + ((qb as f64 - qa as f64) / b as f64).exp());
+            assert!((pa as f64 - expected).abs() <= 1.0);
+        }
+    }
+
+    #[test]
+    fn buying_one_side_moves_both_prices() {
+        let curve = LmsrCurve { liquidity: 100_000_000, other_supply: 0 };
+        let tokens = curve.tokens_for_usdc(0, 30_000_000).unwrap();
+        let other = LmsrCurve { liquidity: 100_000_000, other_supply: tokens };
+        assert!(curve.spot_price(tokens).unwrap() > 500_000);
+        assert!(other.spot_price(0).unwrap() < 500_000);
+    }
+
+    #[test]
+    fn cost_brackets_f64() {
+        let mut rng = Rng(0xc057);
+        for _ in 0..1_000 {
+            let curve = random_curve(&mut rng);
+            let s0 = rng.below(50_000_000_000);
+            let s1 = s0 + rng.below(50_000_000_000);
+            let (b, other) = (curve.liquidity as f64, curve.other_supply as f64);
+            let exact = cost_f64(b, s1 as f64, other) - cost_f64(b, s0 as f64, other);
+
+            let upper = curve.cost(s0, s1, true).unwrap() as f64;
+            let lower = curve.cost(s0, s1, false).unwrap() as f64;
+            // f64 loses ~1e-16 of the supplies to cancellation
+            let tolerance = 1e-15 * (s1 as f64 + other) + 1e-6;
+            assert!(lower <= exact + tolerance && upper >= exact - tolerance, "{} .. {} for {}", lower, upper, exact);
+            assert!(upper - lower <= 2.0 + b * 2f64.powi(-46), "{} .. {}", lower, upper);
+        }
+    }
+
+    #[test]
+    fn tokens_for_usdc_is_maximal() {
+        let mut rng = Rng(0x70c5);
+        for _ in 0..300 {
+            let curve = random_curve(&mut rng);
+            let supply = rng.below(50_000_000_000);
+            let usdc = rng.below(100_000_000_000);
+            let tokens = curve.tokens_for_usdc(supply, usdc).unwrap();
+            assert!(curve.buy_cost(supply, tokens).unwrap() <= usdc);
+            assert!(!curve.buy_cost(supply, tokens + 1).is_ok_and(|c| c <= usdc));
+        }
+    }
+
+    #[test]
+    fn loss_is_bounded_by_the_subsidy() {
+        let mut rng = Rng(0x1055);
+        for _ in 0..50 {
+            let b = MIN_LIQUIDITY + rng.below(1_000_000_000);
+            let mut supplies = [0u64; 2];
+            let mut pool = subsidy(b).unwrap();
+            assert!(to_f64(pool as u128 * ONE) >= b as f64 * std::f64::consts::LN_2);
+
+            for _ in 0..40 {
+                let side = rng.below(2) as usize;
+                let curve = LmsrCurve { liquidity: b, other_supply: supplies[1 - side] };
+                if rng.below(3) == 0 && supplies[side] > 0 {
+                    let amount = 1 + rng.below(supplies[side]);
+                    pool -= curve.sell_proceeds(supplies[side], amount).unwrap();
+                    supplies[side] -= amount;
+                } else {
+                    let usdc = rng.below(5_000_000_000);
+                    supplies[side] += curve.tokens_for_usdc(supplies[side], usdc).unwrap();
+                    pool += usdc;
+                }
+                // Either side winning is covered at one USDC per token
+                assert!(pool >= supplies[0].max(supplies[1]));
+            }
+
+            // Paying every token its current price is covered too
+            let value = |side: usize| {
+                let curve = LmsrCurve { liquidity: b, other_supply: supplies[1 - side] };
+                supplies[side] as u128 * curve.spot_price(supplies[side]).unwrap() as u128 / TOKEN_SCALE
+            };
+            assert!(value(0) + value(1) <= pool as u128);
+        }
+    }
+}
@@ -0,0 +1,335 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program, BN } from "@coral-xyz/anchor";
+import { Sportsxchange } from "../target/types/sportsxchange";
+import { PublicKey, Keypair, SystemProgram } from "@solana/web3.js";
+import {
+  createMint,
+  createAccount,
+  createAssociatedTokenAccount,
+  mintTo,
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import {
+  configPda,
+  createMarket,
+  expectError,
+  fundTrader,
+  getUsdcMint,
+  marketPda,
+  traderAccounts,
+  TraderAccounts,
+} from "./helpers";
+
+// Every account that BuyOnCurve / SellOnCurve touch is swapped for a
+// look-alike; each substitution must be rejected before any tokens move.
+describe("sportsxchange - Account Validation", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Sportsxchange as Program<Sportsxchange>;
+  const authority = provider.wallet as anchor.Wallet;
+
+  const gameId = "2024-WEEK4-BUF-MIA";
+  const otherGameId = "2024-WEEK4-GB-CHI";
+
+  type MarketAccounts = {
+    market: PublicKey;
+    teamAMint: PublicKey;
+    teamBMint: PublicKey;
+    usdcVault: PublicKey;
+    feeVault: PublicKey;
+  };
+
+  let usdcMint: PublicKey;
+  let fakeUsdcMint: PublicKey;
+  let target: MarketAccounts;
+  let other: MarketAccounts;
+
+  let trader: Keypair;
+  let victim: Keypair;
+  let traderAtas: TraderAccounts;
+  let victimAtas: TraderAccounts;
+  let traderFakeUsdc: PublicKey;
+  let traderLooseUsdc: PublicKey;
+
+  const derive = (id: string): MarketAccounts => {
+    const pda = (seed: string) => marketPda(program, seed, id);
+    return {
+      market: pda("market"),
+      teamAMint: pda("team_a_mint"),
+      teamBMint: pda("team_b_mint"),
+      usdcVault: pda("usdc_vault"),
+      feeVault: pda("fee_vault"),
+    };
+  };
+
+  async function createTestMarket(id: string): Promise<MarketAccounts> {
+    await createMarket(program, id, usdcMint).rpc();
+    return derive(id);
+  }
+
+  const buyAccounts = (overrides: Record<string, PublicKey> = {}) => ({
+    buyer: trader.publicKey,
+    config: configPda(program),
+    market: target.market,
+    teamAMint: target.teamAMint,
+    teamBMint: target.teamBMint,
+    buyerTeamAAccount: traderAtas.teamAAccount,
+    buyerTeamBAccount: traderAtas.teamBAccount,
+    buyerUsdc: traderAtas.usdc,
+    usdcVault: target.usdcVault,
+    feeVault: target.feeVault,
+    position: null,
+    tokenProgram: TOKEN_PROGRAM_ID,
+    associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    systemProgram: SystemProgram.programId,
+    ...overrides,
+  });
+
+  const sellAccounts = (overrides: Record<string, PublicKey> = {}) => ({
+    seller: trader.publicKey,
+    config: configPda(program),
+    market: target.market,
+    teamAMint: target.teamAMint,
+    teamBMint: target.teamBMint,
+    sellerTeamAAccount: traderAtas.teamAAccount,
+    sellerTeamBAccount: traderAtas.teamBAccount,
+    sellerUsdc: traderAtas.usdc,
+    usdcVault: target.usdcVault,
+    feeVault: target.feeVault,
+    position: null,
+    tokenProgram: TOKEN_PROGRAM_ID,
+    systemProgram: SystemProgram.programId,
+    ...overrides,
+  });
+
+  const buy = (overrides: Record<string, PublicKey> = {}) =>
+    program.methods
+      .buyOnCurve(0, new BN(1e6), new BN(0), null)
+      .accountsStrict(buyAccounts(overrides) as any)
+      .signers([trader])
+      .rpc();
+
+  const sell = (overrides: Record<string, PublicKey> = {}) =>
+    program.methods
+      .sellOnCurve(0, new BN(1_000), new BN(0), null)
+      .accountsStrict(sellAccounts(overrides) as any)
+      .signers([trader])
+      .rpc();
+
+  console.log("\n" + "=".repeat(80));
+  console.log("🛡️  SportsXchange Account Validation Tests");
+  console.log("=".repeat(80));
+
+  before(async () => {
+    usdcMint = await getUsdcMint(program, provider);
+    fakeUsdcMint = await createMint(
+      provider.connection,
+      authority.payer,
+      authority.publicKey,
+      null,
+      6
+    );
+
+    target = await createTestMarket(gameId);
+    other = await createTestMarket(otherGameId);
+
+    trader = Keypair.generate();
+    victim = Keypair.generate();
+    for (const owner of [trader, victim]) {
+      await fundTrader(program, provider, [gameId], usdcMint, owner);
+    }
+    traderAtas = traderAccounts(program, gameId, usdcMint, trader.publicKey);
+    victimAtas = traderAccounts(program, gameId, usdcMint, victim.publicKey);
+
+    traderFakeUsdc = await createAssociatedTokenAccount(
+      provider.connection,
+      authority.payer,
+      fakeUsdcMint,
+      trader.publicKey
+    );
+    await mintTo(
+      provider.connection,
+      authority.payer,
+      fakeUsdcMint,
+      traderFakeUsdc,
+      authority.payer,
+      1_000 * 1e6
+    );
+
+    // A real-USDC account owned by the trader that is not the market vault
+    traderLooseUsdc = await createAccount(
+      provider.connection,
+      authority.payer,
+      usdcMint,
+      trader.publicKey,
+      Keypair.generate()
+    );
+
+    // Seed a position so sells have something to burn
+    await buy();
+  });
+
+  describe("buy_on_curve", () => {
+    it("Rejects a team A mint from another market", async () => {
+      await expectError(
+        buy({ teamAMint: other.teamAMint }),
+        "ConstraintHasOne",
+        "ConstraintSeeds"
+      );
+    });
+
+    it("Rejects team mints passed in swapped order", async () => {
+      await expectError(
+        buy({ teamAMint: target.teamBMint, teamBMint: target.teamAMint }),
+        "ConstraintHasOne",
+        "ConstraintSeeds"
+      );
+    });
+
+    it("Rejects a vault belonging to another market", async () => {
+      await expectError(
+        buy({ usdcVault: other.usdcVault }),
+        "ConstraintHasOne",
+        "ConstraintSeeds"
+      );
+    });
+
+    it("Rejects a trader-owned account posing as the vault", async () => {
+      await expectError(
+        buy({ usdcVault: traderLooseUsdc }),
+        "ConstraintHasOne",
+        "ConstraintSeeds"
+      );
+    });
+
+    it("Rejects a fee vault belonging to another market", async () => {
+      await expectError(
+        buy({ feeVault: other.feeVault }),
+        "ConstraintHasOne",
+        "ConstraintSeeds"
+      );
+    });
+
+    it("Rejects payment from a token account of a different mint", async () => {
+      await expectError(
+        buy({ buyerUsdc: traderFakeUsdc }),
+        "ConstraintTokenMint"
+      );
+    });
+
+    it("Rejects payment from an account the buyer does not own", async () => {
+      await expectError(
+        buy({ buyerUsdc: victimAtas.usdc }),
+        "ConstraintTokenOwner"
+      );
+    });
+
+    it("Rejects a different market with this market's mints", async () => {
+      await expectError(
+        buy({ market: other.market }),
+        "ConstraintHasOne",
+        "ConstraintSeeds"
+      );
+    });
+  });
+
+  describe("sell_on_curve", () => {
+    it("Rejects a team A mint from another market", async () => {
+      await expectError(
+        sell({ teamAMint: other.teamAMint }),
+        "ConstraintHasOne",
+        "ConstraintSeeds"
+      );
+    });
+
+    it("Rejects team mints passed in swapped order", async () => {
+      await expectError(
+        sell({ teamAMint: target.teamBMint, teamBMint: target.teamAMint }),
+        "ConstraintHasOne",
+        "ConstraintSeeds"
+      );
+    });
+
+    it("Rejects a vault belonging to another market", async () => {
+      await expectError(
+        sell({ usdcVault: other.usdcVault }),
+        "ConstraintHasOne",
+        "ConstraintSeeds"
+      );
+    });
+
+    it("Rejects a trader-owned account posing as the vault", async () => {
+      await expectError(
+        sell({ usdcVault: traderLooseUsdc }),
+        "ConstraintHasOne",
+        "ConstraintSeeds"
+      );
+    });
+
+    it("Rejects a fee vault belonging to another market", async () => {
+      await expectError(
+        sell({ feeVault: other.feeVault }),
+        "ConstraintHasOne",
+        "ConstraintSeeds"
+      );
+    });
+
+    it("Rejects a payout account of a different mint", async () => {
+      await expectError(
+        sell({ sellerUsdc: traderFakeUsdc }),
+        "ConstraintTokenMint"
+      );
+    });
+
+    it("Rejects a payout account owned by someone else", async () => {
+      await expectError(
+        sell({ sellerUsdc: victimAtas.usdc }),
+        "ConstraintTokenOwner"
+      );
+    });
+
+    it("Rejects a different market with this market's mints", async () => {
+      await expectError(
+        sell({ market: other.market }),
+        "ConstraintHasOne",
+        "ConstraintSeeds"
+      );
+    });
+  });
+
+  describe("collateral allowlist", () => {
+    it("Rejects a market backed by a mint that is not allowlisted", async () => {
+      const fakeGameId = "2024-WEEK4-FAKE-USDC";
+      await expectError(
+        createMarket(program, fakeGameId, fakeUsdcMint).rpc(),
+        "CollateralNotAllowed"
+      );
+    });
+
+    it("Rejects allowlist changes from a non-admin", async () => {
+      await expectError(
+        program.methods
+          .addCollateralMint(fakeUsdcMint)
+          .accounts({ admin: trader.publicKey } as any)
+          .signers([trader])
+          .rpc(),
+        "ConstraintHasOne"
+      );
+    });
+
+    it("Records the collateral mint on the market", async () => {
+      const market = await program.account.marketV2.fetch(target.market);
+      assert.isTrue(market.usdcMint.equals(usdcMint));
+      assert.strictEqual(market.usdcDecimals, 6);
+    });
+  });
+
+  it("Still accepts the correctly derived accounts", async () => {
+    await sell();
+    const market = await program.account.marketV2.fetch(target.market);
+    assert.isAbove(market.teamASupply.toNumber(), 0);
+  });
+});
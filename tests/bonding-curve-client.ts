@@ -38,7 +38,8 @@ export class BondingCurveClient {
     teamA: string,
     teamB: string,
     k: number = 0.0001,  // Default from simulation
-    n: number = 1.5      // Default from simulation
+    n: number = 1.5,     // Default from simulation
+    tradingCloseTs: number = Math.floor(Date.now() / 1000) + 7 * 24 * 60 * 60  // Game kickoff
   ): Promise<{
     marketPda: PublicKey;
     teamAMint: PublicKey;
@@ -72,7 +73,7 @@ export class BondingCurveClient {
     );
 
     const tx = await this.program.methods
-      .createMarketV2(gameId, teamA, teamB, kScaled, nScaled)
+      .createMarketV2(gameId, teamA, teamB, kScaled, nScaled, { linear: {} }, { moneyline: {} }, new BN(tradingCloseTs), null)
       .accounts({
         authority: this.provider.wallet.publicKey,
         market: marketPda,
@@ -180,7 +181,8 @@ export class BondingCurveClient {
       .buyOnCurve(
         team === 'A' ? 0 : 1,
         new BN(usdcAmount * 1e6),  // Convert to USDC decimals
-        minTokensOut
+        minTokensOut,
+        null
       )
       .accounts({
         buyer: this.provider.wallet.publicKey,
@@ -271,7 +273,8 @@ export class BondingCurveClient {
       .sellOnCurve(
         team === 'A' ? 0 : 1,
         new BN(tokenAmount * 1e6),
-        minUsdcOut
+        minUsdcOut,
+        null
       )
       .accounts({
         seller: this.provider.wallet.publicKey,
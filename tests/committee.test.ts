@@ -0,0 +1,214 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program, BN } from "@coral-xyz/anchor";
+import { Sportsxchange } from "../target/types/sportsxchange";
+import { PublicKey, Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { assert } from "chai";
+import {
+  closeTs,
+  createMarket,
+  eventsOf,
+  expectError,
+  getUsdcMint,
+  marketPda,
+  sleep,
+} from "./helpers";
+
+describe("sportsxchange - Resolver Committee", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Sportsxchange as Program<Sportsxchange>;
+  const authority = provider.wallet as anchor.Wallet;
+
+  const committeeName = "playoffs";
+  const agreedGame = "2024-WILDCARD-LAC-HOU";
+  const contestedGame = "2024-WILDCARD-PIT-BAL";
+  const cancelledGame = "2024-WILDCARD-GB-PHI";
+  const scalarGame = "2024-WILDCARD-HENRY-RUSH-YDS";
+
+  const members = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const outsider = Keypair.generate();
+
+  const ballotPda = (gameId: string) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("ballot"), marketPda(program, "market", gameId).toBuffer()],
+      program.programId
+    )[0];
+
+  const committeePda = PublicKey.findProgramAddressSync(
+    [Buffer.from("committee"), Buffer.from(committeeName)],
+    program.programId
+  )[0];
+
+  const createCommittee = (name: string, keys: PublicKey[], threshold: number) =>
+    program.methods
+      .createCommittee(name, keys, threshold)
+      .accounts({ admin: authority.publicKey } as any)
+      .rpc();
+
+  const assign = (gameId: string) =>
+    program.methods
+      .assignCommittee()
+      .accounts({
+        admin: authority.publicKey,
+        market: marketPda(program, "market", gameId),
+        committee: committeePda,
+      } as any)
+      .rpc();
+
+  const vote = (gameId: string, voter: Keypair, outcome: number) =>
+    program.methods
+      .voteOutcome(outcome)
+      .accounts({
+        voter: voter.publicKey,
+        market: marketPda(program, "market", gameId),
+        ballot: ballotPda(gameId),
+        committee: committeePda,
+      } as any)
+      .signers([voter])
+      .rpc({ commitment: "confirmed" });
+
+  console.log("\n" + "=".repeat(80));
+  console.log("🧑‍⚖️ SportsXchange Resolver Committee Tests");
+  console.log("=".repeat(80));
+
+  before(async () => {
+    const usdcMint = await getUsdcMint(program, provider);
+
+    for (const signer of [...members, outsider]) {
+      const sig = await provider.connection.requestAirdrop(signer.publicKey, LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(sig);
+    }
+
+    for (const gameId of [agreedGame, contestedGame, cancelledGame]) {
+      await createMarket(program, gameId, usdcMint, { tradingCloseTs: closeTs(3) }).rpc();
+    }
+    await createMarket(program, scalarGame, usdcMint, {
+      kind: { scalar: { lower: new BN(50), upper: new BN(150) } },
+      tradingCloseTs: closeTs(3),
+    }).rpc();
+  });
+
+  it("Requires a majority threshold and distinct members", async () => {
+    const keys = members.map((m) => m.publicKey);
+    await expectError(createCommittee("minority", keys, 1), "InvalidCommittee");
+    await expectError(createCommittee("duplicates", [keys[0], keys[0], keys[1]], 2), "InvalidCommittee");
+  });
+
+  it("Creates a 2-of-3 committee and assigns markets to it", async () => {
+    await createCommittee(committeeName, members.map((m) => m.publicKey), 2);
+
+    const committee = await program.account.resolverCommittee.fetch(committeePda);
+    assert.strictEqual(committee.members.length, 3);
+    assert.strictEqual(committee.threshold, 2);
+
+    for (const gameId of [agreedGame, contestedGame, cancelledGame]) {
+      await assign(gameId);
+    }
+
+    const market = await program.account.marketV2.fetch(marketPda(program, "market", agreedGame));
+    assert.deepEqual(Object.keys(market.resolutionState), ["committee"]);
+
+    await sleep(4_000);
+    await expectError(
+      program.methods
+        .resolveMarket(0)
+        .accounts({
+          resolver: authority.publicKey,
+          market: marketPda(program, "market", agreedGame),
+        } as any)
+        .rpc(),
+      "CommitteeResolution"
+    );
+  });
+
+  it("Refuses scalar markets, which votes cannot settle", async () => {
+    await expectError(assign(scalarGame), "InvalidMarketKind");
+
+    const market = await program.account.marketV2.fetch(marketPda(program, "market", scalarGame));
+    assert.deepEqual(Object.keys(market.resolutionState), ["idle"]);
+  });
+
+  it("Only counts one vote per member", async () => {
+    await expectError(vote(agreedGame, outsider, 1), "NotCommitteeMember");
+
+    await vote(agreedGame, members[0], 1);
+    await expectError(vote(agreedGame, members[0], 1), "AlreadyVoted");
+
+    const market = await program.account.marketV2.fetch(marketPda(program, "market", agreedGame));
+    assert.isFalse(market.isResolved);
+  });
+
+  it("Resolves on the threshold-th matching vote", async () => {
+    const sig = await vote(agreedGame, members[2], 1);
+
+    const market = await program.account.marketV2.fetch(marketPda(program, "market", agreedGame));
+    assert.isTrue(market.isResolved);
+    assert.strictEqual(market.winner, 1);
+
+    const events = await eventsOf(program, provider, sig);
+    assert.deepEqual(
+      events.map((e) => e.name),
+      ["committeeVoteEvent", "marketResolvedEvent"]
+    );
+    assert.isTrue(events[1].data.resolver.equals(committeePda));
+  });
+
+  it("Leaves a deadlocked market contested for the admin", async () => {
+    await vote(contestedGame, members[0], 0);
+    await vote(contestedGame, members[1], 1);
+    const sig = await vote(contestedGame, members[2], 2);
+
+    const [event] = await eventsOf(program, provider, sig);
+    assert.isTrue(event.data.contested);
+    assert.deepEqual(event.data.votes, [0, 1, 2]);
+
+    let market = await program.account.marketV2.fetch(marketPda(program, "market", contestedGame));
+    assert.deepEqual(Object.keys(market.resolutionState), ["contested"]);
+    assert.isFalse(market.isResolved);
+
+    await program.methods
+      .resolveContested(0)
+      .accounts({
+        admin: authority.publicKey,
+        market: marketPda(program, "market", contestedGame),
+      } as any)
+      .rpc();
+
+    market = await program.account.marketV2.fetch(marketPda(program, "market", contestedGame));
+    assert.isTrue(market.isResolved);
+    assert.strictEqual(market.winner, 0);
+
+    const ballot = await program.account.committeeBallot.fetch(ballotPda(contestedGame));
+    assert.deepEqual(ballot.votes, [0, 1, 2]);
+  });
+
+  it("Lets the admin void a committee market whose game was cancelled", async () => {
+    await vote(cancelledGame, members[0], 0);
+
+    const voidMarket = (admin: Keypair) =>
+      program.methods
+        .voidCommitteeMarket()
+        .accounts({
+          admin: admin.publicKey,
+          market: marketPda(program, "market", cancelledGame),
+        } as any)
+        .signers([admin])
+        .rpc({ commitment: "confirmed" });
+    await expectError(voidMarket(outsider), "ConstraintHasOne");
+
+    const sig = await voidMarket(authority.payer);
+
+    const market = await program.account.marketV2.fetch(
+      marketPda(program, "market", cancelledGame)
+    );
+    assert.isTrue(market.isVoided);
+    assert.deepEqual(Object.keys(market.resolutionState), ["idle"]);
+
+    const [event] = await eventsOf(program, provider, sig);
+    assert.strictEqual(event.name, "marketVoidedEvent");
+    assert.isTrue(event.data.authority.equals(authority.publicKey));
+
+    await expectError(vote(cancelledGame, members[1], 0), "NotCommitteeVoting");
+  });
+});
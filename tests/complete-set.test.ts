@@ -0,0 +1,163 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program, BN } from "@coral-xyz/anchor";
+import { Sportsxchange } from "../target/types/sportsxchange";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import {
+  createAssociatedTokenAccount,
+  getAssociatedTokenAddress,
+  mintTo,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import {
+  claim,
+  closeTs,
+  createMarket,
+  eventsOf,
+  expectError,
+  fundTrader,
+  getUsdcMint,
+  holderAccounts,
+  marketPda,
+  sleep,
+  tokenBalance,
+  TraderAccounts,
+  traderAccounts,
+} from "./helpers";
+
+describe("sportsxchange - Complete Sets", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Sportsxchange as Program<Sportsxchange>;
+  const authority = provider.wallet as anchor.Wallet;
+
+  const gameId = "2024-WEEK10-SF-SEA";
+  const linearGameId = "2024-WEEK10-SF-SEA-LINEAR";
+
+  let usdcMint: PublicKey;
+  let trader: Keypair;
+  let accounts: TraderAccounts;
+
+  const pda = (seed: string) => marketPda(program, seed, gameId);
+
+  const mintSet = (amount: number, id = gameId) =>
+    program.methods
+      .mintCompleteSet(new BN(amount))
+      .accounts(holderAccounts(program, id, usdcMint, trader.publicKey) as any)
+      .signers([trader])
+      .rpc({ commitment: "confirmed" });
+
+  const redeemSet = (amount: number) =>
+    program.methods
+      .redeemCompleteSet(new BN(amount))
+      .accounts(holderAccounts(program, gameId, usdcMint, trader.publicKey) as any)
+      .signers([trader])
+      .rpc({ commitment: "confirmed" });
+
+  console.log("\n" + "=".repeat(80));
+  console.log("🧩 SportsXchange Complete Set Tests");
+  console.log("=".repeat(80));
+
+  before(async () => {
+    usdcMint = await getUsdcMint(program, provider);
+
+    // The LMSR creator funds the subsidy from their own USDC account
+    let authorityUsdc = await getAssociatedTokenAddress(usdcMint, authority.publicKey);
+    if (!(await provider.connection.getAccountInfo(authorityUsdc))) {
+      authorityUsdc = await createAssociatedTokenAccount(
+        provider.connection,
+        authority.payer,
+        usdcMint,
+        authority.publicKey
+      );
+    }
+    await mintTo(provider.connection, authority.payer, usdcMint, authorityUsdc, authority.payer, 100 * 1e6);
+
+    await createMarket(program, gameId, usdcMint, {
+      teamA: "SF",
+      teamB: "SEA",
+      basePrice: 0,
+      slope: 0,
+      curveType: { lmsr: { liquidity: new BN(100 * 1e6) } },
+      tradingCloseTs: closeTs(6),
+      accounts: { authorityUsdc },
+    }).rpc();
+    await createMarket(program, linearGameId, usdcMint, { teamA: "SF", teamB: "SEA" }).rpc();
+
+    trader = Keypair.generate();
+    await fundTrader(program, provider, [gameId, linearGameId], usdcMint, trader, 100 * 1e6);
+    accounts = traderAccounts(program, gameId, usdcMint, trader.publicKey);
+  });
+
+  it("Mints one of each team token per USDC without moving the curves", async () => {
+    const before = await program.account.marketV2.fetch(pda("market"));
+    const vaultBefore = await tokenBalance(provider, pda("usdc_vault"));
+    const sig = await mintSet(10 * 1e6);
+
+    assert.strictEqual(await tokenBalance(provider, accounts.teamAAccount), 10 * 1e6);
+    assert.strictEqual(await tokenBalance(provider, accounts.teamBAccount), 10 * 1e6);
+    assert.strictEqual(await tokenBalance(provider, accounts.usdc), 90 * 1e6);
+    assert.strictEqual((await tokenBalance(provider, pda("usdc_vault"))) - vaultBefore, 10 * 1e6);
+
+    const market = await program.account.marketV2.fetch(pda("market"));
+    assert.strictEqual(market.completeSets.toNumber(), 10 * 1e6);
+    assert.isTrue(market.teamASupply.eq(before.teamASupply));
+    assert.isTrue(market.poolValue.eq(before.poolValue));
+
+    const [event] = await eventsOf(program, provider, sig);
+    assert.strictEqual(event.name, "completeSetEvent");
+    assert.deepEqual(Object.keys(event.data.action), ["mint"]);
+    assert.strictEqual(event.data.completeSets.toNumber(), 10 * 1e6);
+  });
+
+  it("Redeems pairs for USDC", async () => {
+    await redeemSet(4 * 1e6);
+
+    assert.strictEqual(await tokenBalance(provider, accounts.teamAAccount), 6 * 1e6);
+    assert.strictEqual(await tokenBalance(provider, accounts.teamBAccount), 6 * 1e6);
+    assert.strictEqual(await tokenBalance(provider, accounts.usdc), 94 * 1e6);
+
+    const market = await program.account.marketV2.fetch(pda("market"));
+    assert.strictEqual(market.completeSets.toNumber(), 6 * 1e6);
+  });
+
+  it("Rejects zero amounts and redemptions beyond the outstanding sets", async () => {
+    for (const [attempt, error] of [
+      [() => mintSet(0), "ZeroAmount"],
+      [() => redeemSet(7 * 1e6), "InsufficientCompleteSets"],
+    ] as const) {
+      await expectError(attempt(), error);
+    }
+  });
+
+  it("Only takes sets on LMSR markets", async () => {
+    await expectError(mintSet(1e6, linearGameId), "CompleteSetsRequireLmsr");
+  });
+
+  it("Stops minting once trading closes", async () => {
+    await sleep(7_000);
+
+    await expectError(mintSet(50 * 1e6), "TradingClosed");
+    assert.strictEqual(await tokenBalance(provider, accounts.usdc), 94 * 1e6);
+
+    const market = await program.account.marketV2.fetch(pda("market"));
+    assert.strictEqual(market.completeSets.toNumber(), 6 * 1e6);
+  });
+
+  it("Pays each outstanding set exactly one USDC at resolution", async () => {
+    await program.methods
+      .resolveMarket(0)
+      .accounts({ resolver: authority.publicKey, market: pda("market") } as any)
+      .rpc();
+
+    const market = await program.account.marketV2.fetch(pda("market"));
+    assert.strictEqual(market.completeSets.toNumber(), 0);
+    assert.strictEqual(market.teamASupply.toNumber(), 6 * 1e6);
+    assert.strictEqual(market.teamAPool.toNumber(), 6 * 1e6);
+
+    await claim(program, gameId, usdcMint, trader).rpc();
+    assert.strictEqual(await tokenBalance(provider, accounts.usdc), 100 * 1e6);
+
+    await expectError(mintSet(1e6), "AlreadyResolved");
+  });
+});
@@ -0,0 +1,225 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program, BN } from "@coral-xyz/anchor";
+import { Sportsxchange } from "../target/types/sportsxchange";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import {
+  buy,
+  createMarket,
+  eventsOf,
+  expectError,
+  fundTrader,
+  getUsdcMint,
+  marketPda,
+  openTeamAccounts,
+  traderAccounts,
+} from "./helpers";
+
+describe("sportsxchange - Curve Selection", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Sportsxchange as Program<Sportsxchange>;
+
+  let usdcMint: PublicKey;
+  let trader: Keypair;
+  let traderUsdc: PublicKey;
+
+  async function createCurveMarket(
+    gameId: string,
+    curveType: any,
+    basePrice = 100_000,
+    slope = 900_000
+  ) {
+    const sig = await createMarket(program, gameId, usdcMint, { curveType, basePrice, slope }).rpc({
+      commitment: "confirmed",
+    });
+    await openTeamAccounts(program, provider, gameId, trader.publicKey);
+    return sig;
+  }
+
+  const buyTeamA = (gameId: string, usdcAmount: number, deadline: BN | null = null) =>
+    buy(program, gameId, usdcMint, trader, 0, usdcAmount, { deadline }).rpc({
+      commitment: "confirmed",
+    });
+
+  console.log("\n" + "=".repeat(80));
+  console.log("📈 SportsXchange Curve Selection Tests");
+  console.log("=".repeat(80));
+
+  before(async () => {
+    usdcMint = await getUsdcMint(program, provider);
+
+    trader = Keypair.generate();
+    traderUsdc = await fundTrader(program, provider, [], usdcMint, trader);
+  });
+
+  it("Stores the chosen curve on the market", async () => {
+    const gameId = "2024-CURVE-SIGMOID";
+    const curveType = { sigmoid: { midpoint: new BN(100 * 1e6), width: new BN(20 * 1e6) } };
+    const sig = await createCurveMarket(gameId, curveType);
+
+    const market = await program.account.marketV2.fetch(marketPda(program, "market", gameId));
+    assert.deepEqual(Object.keys(market.curveType), ["sigmoid"]);
+    assert.isTrue((market.curveType as any).sigmoid.width.eq(curveType.sigmoid.width));
+
+    const [event] = await eventsOf(program, provider, sig);
+    assert.strictEqual(event.name, "marketCreatedEvent");
+    assert.deepEqual(Object.keys(event.data.curveType), ["sigmoid"]);
+  });
+
+  it("Prices trades on the chosen curve", async () => {
+    const gameId = "2024-CURVE-CONSTANT";
+    await createCurveMarket(gameId, { constant: {} }, 250_000, 0);
+
+    const sig = await buyTeamA(gameId, 10 * 1e6);
+
+    // 10 USDC at a flat 0.25 USDC per token
+    const [event] = await eventsOf(program, provider, sig);
+    assert.strictEqual(event.data.tokenAmount.toNumber(), 40 * 1e6);
+    assert.strictEqual(event.data.priceBefore.toNumber(), 250_000);
+    assert.strictEqual(event.data.priceAfter.toNumber(), 250_000);
+  });
+
+  it("Prices a fractional power curve", async () => {
+    const gameId = "2024-CURVE-POWER";
+    await createCurveMarket(gameId, { power: { exponent: 150 } }, 100_000, 10_000);
+
+    const sig = await buyTeamA(gameId, 10 * 1e6);
+
+    const market = await program.account.marketV2.fetch(marketPda(program, "market", gameId));
+    assert.strictEqual((market.curveType as any).power.exponent, 150);
+
+    // price = 0.1 + 0.01 * s^1.5 USDC at the supply the buy reached
+    const [event] = await eventsOf(program, provider, sig);
+    const supply = event.data.tokenAmount.toNumber() / 1e6;
+    const expected = 100_000 + 10_000 * Math.pow(supply, 1.5);
+    assert.approximately(event.data.priceAfter.toNumber(), expected, 2);
+  });
+
+  it("Quotes exactly what the trade then fills", async () => {
+    const gameId = "2024-CURVE-QUOTE";
+    await createCurveMarket(gameId, { linear: {} }, 100_000, 10_000);
+
+    const quote = await program.methods
+      .quote(0, { buy: {} }, new BN(10 * 1e6), false)
+      .accounts({ market: marketPda(program, "market", gameId) } as any)
+      .view();
+
+    const sig = await buyTeamA(gameId, 10 * 1e6);
+    const [event] = await eventsOf(program, provider, sig);
+    assert.isTrue(quote.tokensOut.eq(event.data.tokenAmount));
+    assert.isTrue(quote.priceAfter.eq(event.data.priceAfter));
+    assert.isTrue(quote.fee.eq(event.data.protocolFee.add(event.data.creatorFee)));
+    assert.strictEqual(
+      quote.averagePrice.toNumber(),
+      Math.floor((10 * 1e6 * 1e6) / quote.tokensOut.toNumber())
+    );
+    assert.isAbove(quote.priceImpactBps.toNumber(), 0);
+  });
+
+  it("Buys an exact number of tokens and sells for exact USDC", async () => {
+    const gameId = "2024-CURVE-EXACT";
+    await createCurveMarket(gameId, { linear: {} }, 100_000, 10_000);
+    const accounts = {
+      teamAMint: marketPda(program, "team_a_mint", gameId),
+      teamBMint: marketPda(program, "team_b_mint", gameId),
+      usdcVault: marketPda(program, "usdc_vault", gameId),
+      feeVault: marketPda(program, "fee_vault", gameId),
+      market: marketPda(program, "market", gameId),
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+    const { teamAAccount, teamBAccount } = traderAccounts(
+      program,
+      gameId,
+      usdcMint,
+      trader.publicKey
+    );
+
+    const preview = await program.methods
+      .quote(0, { buy: {} }, new BN(25 * 1e6), true)
+      .accounts({ market: accounts.market } as any)
+      .view();
+
+    await expectError(
+      program.methods
+        .buyExactTokens(0, new BN(25 * 1e6), preview.amount.subn(1), null)
+        .accounts({
+          ...accounts,
+          buyer: trader.publicKey,
+          buyerTeamAAccount: teamAAccount,
+          buyerTeamBAccount: teamBAccount,
+          buyerUsdc: traderUsdc,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        } as any)
+        .signers([trader])
+        .rpc(),
+      "SlippageExceeded"
+    );
+
+    const buySig = await program.methods
+      .buyExactTokens(0, new BN(25 * 1e6), preview.amount, null)
+      .accounts({
+        ...accounts,
+        buyer: trader.publicKey,
+        buyerTeamAAccount: teamAAccount,
+        buyerTeamBAccount: teamBAccount,
+        buyerUsdc: traderUsdc,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      } as any)
+      .signers([trader])
+      .rpc({ commitment: "confirmed" });
+    const [bought] = await eventsOf(program, provider, buySig);
+    assert.strictEqual(bought.data.tokenAmount.toNumber(), 25 * 1e6);
+    assert.isTrue(bought.data.usdcAmount.eq(preview.amount));
+
+    const sellSig = await program.methods
+      .sellForExactUsdc(0, new BN(2 * 1e6), new BN(25 * 1e6), null)
+      .accounts({
+        ...accounts,
+        seller: trader.publicKey,
+        sellerTeamAAccount: teamAAccount,
+        sellerTeamBAccount: teamBAccount,
+        sellerUsdc: traderUsdc,
+      } as any)
+      .signers([trader])
+      .rpc({ commitment: "confirmed" });
+    const [sold] = await eventsOf(program, provider, sellSig);
+    assert.strictEqual(sold.data.usdcAmount.toNumber(), 2 * 1e6);
+    assert.isBelow(sold.data.tokenAmount.toNumber(), 25 * 1e6);
+  });
+
+  it("Rejects trades that land after their deadline", async () => {
+    const gameId = "2024-CURVE-DEADLINE";
+    await createCurveMarket(gameId, { linear: {} }, 100_000, 10_000);
+
+    const now = Math.floor(Date.now() / 1000);
+    await expectError(buyTeamA(gameId, 1e6, new BN(now - 3_600)), "DeadlineExceeded");
+
+    const sig = await buyTeamA(gameId, 1e6, new BN(now + 3_600));
+    const [event] = await eventsOf(program, provider, sig);
+    assert.isAbove(event.data.tokenAmount.toNumber(), 0);
+  });
+
+  it("Rejects invalid curve parameters", async () => {
+    for (const [gameId, curveType] of [
+      ["2024-CURVE-BAD-POWER", { power: { exponent: 401 } }],
+      ["2024-CURVE-BAD-SIGMOID", { sigmoid: { midpoint: new BN(0), width: new BN(0) } }],
+    ] as const) {
+      await expectError(createCurveMarket(gameId, curveType), "InvalidCurveParams");
+    }
+  });
+
+  it("Rejects a power or sigmoid curve with a flat zero price", async () => {
+    for (const [gameId, curveType] of [
+      ["2024-CURVE-ZERO-POWER", { power: { exponent: 150 } }],
+      ["2024-CURVE-ZERO-SIGMOID", { sigmoid: { midpoint: new BN(100 * 1e6), width: new BN(20 * 1e6) } }],
+    ] as const) {
+      await expectError(createCurveMarket(gameId, curveType, 0, 0), "InvalidCurveParams");
+    }
+  });
+});
@@ -0,0 +1,132 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Sportsxchange } from "../target/types/sportsxchange";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { assert } from "chai";
+import {
+  buy,
+  claim,
+  createMarket,
+  eventsOf,
+  expectError,
+  fundTrader,
+  getUsdcMint,
+  marketPda,
+  tokenBalance,
+  traderAccounts,
+} from "./helpers";
+
+describe("sportsxchange - Draw Resolution", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Sportsxchange as Program<Sportsxchange>;
+  const authority = provider.wallet as anchor.Wallet;
+
+  const gameId = "2024-WEEK8-NE-NYJ";
+
+  let usdcMint: PublicKey;
+  let alice: Keypair;
+  let bob: Keypair;
+  let carol: Keypair;
+
+  const pda = (seed: string) => marketPda(program, seed, gameId);
+
+  const DRAW = 2;
+
+  console.log("\n" + "=".repeat(80));
+  console.log("🤝 SportsXchange Draw Resolution Tests");
+  console.log("=".repeat(80));
+
+  before(async () => {
+    usdcMint = await getUsdcMint(program, provider);
+
+    await createMarket(program, gameId, usdcMint, { teamA: "NE", teamB: "NYJ" }).rpc();
+
+    alice = Keypair.generate();
+    bob = Keypair.generate();
+    carol = Keypair.generate();
+    for (const trader of [alice, bob, carol]) {
+      await fundTrader(program, provider, [gameId], usdcMint, trader);
+    }
+
+    await buy(program, gameId, usdcMint, alice, 0, 40 * 1e6).rpc();
+    await buy(program, gameId, usdcMint, bob, 1, 10 * 1e6).rpc();
+    await buy(program, gameId, usdcMint, carol, 0, 5 * 1e6).rpc();
+    await buy(program, gameId, usdcMint, carol, 1, 5 * 1e6).rpc();
+
+    await program.methods
+      .haltTrading()
+      .accounts({ authority: authority.publicKey, market: pda("market") } as any)
+      .rpc();
+  });
+
+  it("Rejects an outcome past draw", async () => {
+    await expectError(
+      program.methods
+        .resolveMarket(3)
+        .accounts({ resolver: authority.publicKey, market: pda("market") } as any)
+        .rpc(),
+      "InvalidTeam"
+    );
+  });
+
+  it("Resolves as a draw and splits the pool between the sides", async () => {
+    const before = await program.account.marketV2.fetch(pda("market"));
+
+    const sig = await program.methods
+      .resolveMarket(DRAW)
+      .accounts({ resolver: authority.publicKey, market: pda("market") } as any)
+      .rpc({ commitment: "confirmed" });
+
+    const market = await program.account.marketV2.fetch(pda("market"));
+    const pool = before.poolValue.toNumber();
+    assert.strictEqual(market.winner, DRAW);
+    assert.strictEqual(market.teamAPool.toNumber(), Math.floor(pool / 2));
+    assert.strictEqual(market.teamBPool.toNumber(), pool - Math.floor(pool / 2));
+
+    const [event] = await eventsOf(program, provider, sig);
+    assert.strictEqual(event.name, "marketResolvedEvent");
+    assert.strictEqual(event.data.winner, DRAW);
+  });
+
+  it("Pays a two-sided holder from both halves", async () => {
+    const before = await program.account.marketV2.fetch(pda("market"));
+    const accounts = traderAccounts(program, gameId, usdcMint, carol.publicKey);
+    const heldA = await tokenBalance(provider, accounts.teamAAccount);
+    const heldB = await tokenBalance(provider, accounts.teamBAccount);
+    const usdcBefore = await tokenBalance(provider, accounts.usdc);
+
+    const sig = await claim(program, gameId, usdcMint, carol).rpc({ commitment: "confirmed" });
+
+    const expected =
+      Math.floor((heldA * before.teamAPool.toNumber()) / before.teamASupply.toNumber()) +
+      Math.floor((heldB * before.teamBPool.toNumber()) / before.teamBSupply.toNumber());
+    const paid = (await tokenBalance(provider, accounts.usdc)) - usdcBefore;
+
+    assert.approximately(paid, expected, 1);
+    assert.strictEqual(await tokenBalance(provider, accounts.teamAAccount), 0);
+    assert.strictEqual(await tokenBalance(provider, accounts.teamBAccount), 0);
+
+    const [event] = await eventsOf(program, provider, sig);
+    assert.strictEqual(event.name, "drawClaimedEvent");
+    assert.strictEqual(event.data.payout.toNumber(), paid);
+    assert.strictEqual(event.data.teamAAmount.toNumber(), heldA);
+    assert.strictEqual(event.data.teamBAmount.toNumber(), heldB);
+  });
+
+  it("Pays both single-sided holders and empties the vault", async () => {
+    await claim(program, gameId, usdcMint, alice).rpc();
+    await claim(program, gameId, usdcMint, bob).rpc();
+
+    const market = await program.account.marketV2.fetch(pda("market"));
+    assert.strictEqual(market.teamASupply.toNumber(), 0);
+    assert.strictEqual(market.teamBSupply.toNumber(), 0);
+    assert.strictEqual(market.poolValue.toNumber(), 0);
+    assert.strictEqual(await tokenBalance(provider, pda("usdc_vault")), 0);
+  });
+
+  it("Rejects a second claim", async () => {
+    await expectError(claim(program, gameId, usdcMint, alice).rpc(), "NoWinningTokens");
+  });
+});
@@ -173,7 +173,8 @@ describe("sportsxchange - Edge Cases & Stress Testing", () => {
         await program.methods
           .swapHomeForAway(
             new anchor.BN(amountIn),
-            new anchor.BN(unrealisticMinimum)
+            new anchor.BN(unrealisticMinimum),
+            null
           )
           .accounts({
             market: marketPda,
@@ -224,7 +225,8 @@ describe("sportsxchange - Edge Cases & Stress Testing", () => {
       const tx = await program.methods
         .swapHomeForAway(
           new anchor.BN(amountIn),
-          new anchor.BN(minimumOut)
+          new anchor.BN(minimumOut),
+          null
         )
         .accounts({
           market: marketPda,
@@ -363,7 +365,8 @@ describe("sportsxchange - Edge Cases & Stress Testing", () => {
         await program.methods
           .swapHomeForAway(
             new anchor.BN(0),
-            new anchor.BN(0)
+            new anchor.BN(0),
+            null
           )
           .accounts({
             market: marketPda,
@@ -601,7 +604,8 @@ describe("sportsxchange - Edge Cases & Stress Testing", () => {
       const tx = await program.methods
         .swapHomeForAway(
           new anchor.BN(amountIn),
-          new anchor.BN(minimumOut)
+          new anchor.BN(minimumOut),
+          null
         )
         .accounts({
           market: marketPda,
@@ -652,7 +656,8 @@ describe("sportsxchange - Edge Cases & Stress Testing", () => {
       const tx = await program.methods
         .swapHomeForAway(
           new anchor.BN(amountIn),
-          new anchor.BN(minimumOut)
+          new anchor.BN(minimumOut),
+          null
         )
         .accounts({
           market: marketPda,
@@ -811,7 +816,8 @@ describe("sportsxchange - Edge Cases & Stress Testing", () => {
           await program.methods
             .swapHomeForAway(
               new anchor.BN(swapAmount),
-              new anchor.BN(Math.floor(expectedOut * 0.9))
+              new anchor.BN(Math.floor(expectedOut * 0.9)),
+              null
             )
             .accounts({
               market: marketPda,
@@ -836,7 +842,8 @@ describe("sportsxchange - Edge Cases & Stress Testing", () => {
           await program.methods
             .swapAwayForHome(
               new anchor.BN(swapAmount),
-              new anchor.BN(Math.floor(expectedOut * 0.9))
+              new anchor.BN(Math.floor(expectedOut * 0.9)),
+              null
             )
             .accounts({
               market: marketPda,
@@ -1016,7 +1023,8 @@ describe("sportsxchange - Edge Cases & Stress Testing", () => {
         await program.methods
           .swapHomeForAway(
             new anchor.BN(10_000_000),
-            new anchor.BN(9_000_000)
+            new anchor.BN(9_000_000),
+            null
           )
           .accounts({
             market: marketPda,
@@ -1056,7 +1064,8 @@ describe("sportsxchange - Edge Cases & Stress Testing", () => {
         await program.methods
           .swapAwayForHome(
             new anchor.BN(10_000_000),
-            new anchor.BN(9_000_000)
+            new anchor.BN(9_000_000),
+            null
           )
           .accounts({
             market: marketPda,
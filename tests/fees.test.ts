@@ -0,0 +1,184 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Sportsxchange } from "../target/types/sportsxchange";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import {
+  createAssociatedTokenAccount,
+  getAssociatedTokenAddress,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import {
+  buy,
+  createMarket,
+  eventsOf,
+  expectError,
+  fundTrader,
+  getUsdcMint,
+  marketPda,
+  sell,
+  tokenBalance,
+} from "./helpers";
+
+describe("sportsxchange - Trading Fees", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Sportsxchange as Program<Sportsxchange>;
+  const admin = provider.wallet as anchor.Wallet;
+
+  const gameId = "2024-WEEK6-LAR-SEA";
+  const protocolFeeBps = 100; // 1%
+  const creatorFeeBps = 50; // 0.5%
+
+  let usdcMint: PublicKey;
+  let trader: Keypair;
+  let traderUsdc: PublicKey;
+  let adminUsdc: PublicKey;
+
+  const pda = (seed: string) => marketPda(program, seed, gameId);
+
+  console.log("\n" + "=".repeat(80));
+  console.log("💸 SportsXchange Trading Fee Tests");
+  console.log("=".repeat(80));
+
+  before(async () => {
+    usdcMint = await getUsdcMint(program, provider);
+
+    await program.methods
+      .setFees(protocolFeeBps, creatorFeeBps)
+      .accounts({ admin: admin.publicKey } as any)
+      .rpc();
+
+    await createMarket(program, gameId, usdcMint, { teamA: "LAR", teamB: "SEA" }).rpc();
+
+    // Later markets in other suites should trade fee-free
+    await program.methods
+      .setFees(0, 0)
+      .accounts({ admin: admin.publicKey } as any)
+      .rpc();
+
+    trader = Keypair.generate();
+    traderUsdc = await fundTrader(program, provider, [gameId], usdcMint, trader);
+
+    adminUsdc = await getAssociatedTokenAddress(usdcMint, admin.publicKey);
+    if (!(await provider.connection.getAccountInfo(adminUsdc))) {
+      adminUsdc = await createAssociatedTokenAccount(
+        provider.connection,
+        admin.payer,
+        usdcMint,
+        admin.publicKey
+      );
+    }
+  });
+
+  it("Snapshots fee rates onto the market at creation", async () => {
+    const market = await program.account.marketV2.fetch(pda("market"));
+    assert.strictEqual(market.protocolFeeBps, protocolFeeBps);
+    assert.strictEqual(market.creatorFeeBps, creatorFeeBps);
+    assert.isTrue(market.feeVault.equals(pda("fee_vault")));
+  });
+
+  it("Routes buy fees to the fee vault, not the pool", async () => {
+    const usdcAmount = 100 * 1e6;
+
+    const sig = await buy(program, gameId, usdcMint, trader, 0, usdcAmount).rpc({
+      commitment: "confirmed",
+    });
+
+    const protocolFee = (usdcAmount * protocolFeeBps) / 10_000;
+    const creatorFee = (usdcAmount * creatorFeeBps) / 10_000;
+    const market = await program.account.marketV2.fetch(pda("market"));
+
+    console.log("   💰 Protocol fee:", protocolFee / 1e6, "USDC");
+    console.log("   💰 Creator fee: ", creatorFee / 1e6, "USDC");
+
+    assert.strictEqual(market.protocolFeesAccrued.toNumber(), protocolFee);
+    assert.strictEqual(market.creatorFeesAccrued.toNumber(), creatorFee);
+    assert.strictEqual(
+      market.poolValue.toNumber(),
+      usdcAmount - protocolFee - creatorFee
+    );
+    assert.strictEqual(await tokenBalance(provider, pda("fee_vault")), protocolFee + creatorFee);
+    assert.strictEqual(
+      await tokenBalance(provider, pda("usdc_vault")),
+      market.poolValue.toNumber()
+    );
+
+    const [event] = await eventsOf(program, provider, sig);
+    assert.strictEqual(event.name, "tradeEvent");
+    assert.strictEqual(event.data.protocolFee.toNumber(), protocolFee);
+    assert.strictEqual(event.data.creatorFee.toNumber(), creatorFee);
+    assert.isAbove(event.data.priceAfter.toNumber(), event.data.priceBefore.toNumber());
+  });
+
+  it("Takes sell fees out of the seller's proceeds", async () => {
+    const before = await program.account.marketV2.fetch(pda("market"));
+    const feesBefore = await tokenBalance(provider, pda("fee_vault"));
+    const usdcBefore = await tokenBalance(provider, traderUsdc);
+
+    await sell(program, gameId, usdcMint, trader, 0, 10 * 1e6).rpc();
+
+    const after = await program.account.marketV2.fetch(pda("market"));
+    const grossOut = before.poolValue.toNumber() - after.poolValue.toNumber();
+    const feesTaken = (await tokenBalance(provider, pda("fee_vault"))) - feesBefore;
+    const received = (await tokenBalance(provider, traderUsdc)) - usdcBefore;
+
+    assert.isAbove(feesTaken, 0);
+    assert.strictEqual(received + feesTaken, grossOut);
+  });
+
+  it("Rejects creator fee withdrawal by anyone but the market authority", async () => {
+    await expectError(
+      program.methods
+        .withdrawCreatorFees()
+        .accounts({
+          authority: trader.publicKey,
+          market: pda("market"),
+          feeVault: pda("fee_vault"),
+          destination: traderUsdc,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        } as any)
+        .signers([trader])
+        .rpc(),
+      "ConstraintHasOne"
+    );
+  });
+
+  it("Withdraws creator and protocol fees separately", async () => {
+    const market = await program.account.marketV2.fetch(pda("market"));
+    const start = await tokenBalance(provider, adminUsdc);
+
+    await program.methods
+      .withdrawCreatorFees()
+      .accounts({
+        authority: admin.publicKey,
+        market: pda("market"),
+        feeVault: pda("fee_vault"),
+        destination: adminUsdc,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      } as any)
+      .rpc();
+    const afterCreator = await tokenBalance(provider, adminUsdc);
+
+    await program.methods
+      .withdrawProtocolFees()
+      .accounts({
+        admin: admin.publicKey,
+        market: pda("market"),
+        feeVault: pda("fee_vault"),
+        destination: adminUsdc,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      } as any)
+      .rpc();
+    const afterProtocol = await tokenBalance(provider, adminUsdc);
+
+    assert.strictEqual(afterCreator - start, market.creatorFeesAccrued.toNumber());
+    assert.strictEqual(afterProtocol - afterCreator, market.protocolFeesAccrued.toNumber());
+    assert.strictEqual(await tokenBalance(provider, pda("fee_vault")), 0);
+
+    const drained = await program.account.marketV2.fetch(pda("market"));
+    assert.strictEqual(drained.protocolFeesAccrued.toNumber(), 0);
+    assert.strictEqual(drained.creatorFeesAccrued.toNumber(), 0);
+  });
+});
@@ -0,0 +1,388 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Sportsxchange } from "../target/types/sportsxchange";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  createAssociatedTokenAccount,
+  createMint,
+  getAccount,
+  mintTo,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+const BPF_LOADER_UPGRADEABLE_PROGRAM_ID = new PublicKey(
+  "BPFLoaderUpgradeab1e11111111111111111111111"
+);
+
+let usdcMint: PublicKey | undefined;
+
+export function configPda(program: Program<Sportsxchange>): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("config")],
+    program.programId
+  )[0];
+}
+
+export function programDataPda(program: Program<Sportsxchange>): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [program.programId.toBuffer()],
+    BPF_LOADER_UPGRADEABLE_PROGRAM_ID
+  )[0];
+}
+
+/**
+ * PDA seeded by `seed` and `gameId`, e.g. the "market" account or its
+ * "team_a_mint" and "usdc_vault".
+ */
+export function marketPda(
+  program: Program<Sportsxchange>,
+  seed: string,
+  gameId: string
+): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from(seed), Buffer.from(gameId)],
+    program.programId
+  )[0];
+}
+
+/**
+ * Test USDC mint shared by every suite. The first call creates the protocol
+ * config (the provider wallet is the program's upgrade authority under
+ * `anchor test`) and allowlists the mint as collateral.
+ */
+export async function getUsdcMint(
+  program: Program<Sportsxchange>,
+  provider: anchor.AnchorProvider
+): Promise<PublicKey> {
+  if (usdcMint) return usdcMint;
+
+  const payer = (provider.wallet as anchor.Wallet).payer;
+  const mint = await createMint(
+    provider.connection,
+    payer,
+    payer.publicKey,
+    null,
+    6
+  );
+
+  if (!(await provider.connection.getAccountInfo(configPda(program)))) {
+    await program.methods
+      .initializeConfig()
+      .accounts({
+        admin: provider.wallet.publicKey,
+        programData: programDataPda(program),
+      } as any)
+      .rpc();
+  }
+
+  await program.methods
+    .addCollateralMint(mint)
+    .accounts({ admin: provider.wallet.publicKey } as any)
+    .rpc();
+
+  usdcMint = mint;
+  return mint;
+}
+
+/**
+ * Decode the Anchor events emitted by a confirmed transaction.
+ */
+export async function eventsOf(
+  program: Program<Sportsxchange>,
+  provider: anchor.AnchorProvider,
+  signature: string
+): Promise<anchor.Event[]> {
+  const tx = await provider.connection.getTransaction(signature, {
+    commitment: "confirmed",
+    maxSupportedTransactionVersion: 0,
+  });
+  const parser = new anchor.EventParser(program.programId, program.coder);
+  return Array.from(parser.parseLogs(tx?.meta?.logMessages ?? []));
+}
+
+/**
+ * Trading close time (game kickoff) `seconds` from now, for `createMarketV2`.
+ * Defaults far enough out that no suite runs into it.
+ */
+export function closeTs(seconds = 7 * 24 * 60 * 60): anchor.BN {
+  return new anchor.BN(Math.floor(Date.now() / 1000) + seconds);
+}
+
+export type MarketOptions = {
+  teamA?: string;
+  teamB?: string;
+  basePrice?: number | anchor.BN;
+  slope?: number | anchor.BN;
+  curveType?: any;
+  kind?: any;
+  tradingCloseTs?: anchor.BN;
+  oracle?: PublicKey | null;
+  accounts?: Record<string, PublicKey | null>;
+};
+
+/**
+ * `createMarketV2` for `gameId`, defaulting every argument a suite does not
+ * care about: a HOME/AWAY linear moneyline market closing in a week with no
+ * oracle, created by the provider wallet. Returns the method builder so the
+ * caller picks signers and `rpc` options.
+ */
+export function createMarket(
+  program: Program<Sportsxchange>,
+  gameId: string,
+  usdcMint: PublicKey,
+  options: MarketOptions = {}
+) {
+  return program.methods
+    .createMarketV2(
+      gameId,
+      options.teamA ?? "HOME",
+      options.teamB ?? "AWAY",
+      new anchor.BN(options.basePrice ?? 100_000),
+      new anchor.BN(options.slope ?? 10_000),
+      options.curveType ?? { linear: {} },
+      options.kind ?? { moneyline: {} },
+      options.tradingCloseTs ?? closeTs(),
+      options.oracle ?? null
+    )
+    .accounts({
+      authority: program.provider.publicKey,
+      usdcMint,
+      ...options.accounts,
+    } as any);
+}
+
+export type TraderAccounts = {
+  usdc: PublicKey;
+  teamAAccount: PublicKey;
+  teamBAccount: PublicKey;
+};
+
+/**
+ * `owner`'s USDC and team token associated accounts for `gameId`.
+ */
+export function traderAccounts(
+  program: Program<Sportsxchange>,
+  gameId: string,
+  usdcMint: PublicKey,
+  owner: PublicKey
+): TraderAccounts {
+  const ata = (mint: PublicKey) => anchor.utils.token.associatedAddress({ mint, owner });
+  return {
+    usdc: ata(usdcMint),
+    teamAAccount: ata(marketPda(program, "team_a_mint", gameId)),
+    teamBAccount: ata(marketPda(program, "team_b_mint", gameId)),
+  };
+}
+
+/**
+ * Open `owner`'s team token accounts on `gameId`, whose market must already
+ * exist.
+ */
+export async function openTeamAccounts(
+  program: Program<Sportsxchange>,
+  provider: anchor.AnchorProvider,
+  gameId: string,
+  owner: PublicKey
+): Promise<void> {
+  const payer = (provider.wallet as anchor.Wallet).payer;
+  for (const seed of ["team_a_mint", "team_b_mint"]) {
+    await createAssociatedTokenAccount(
+      provider.connection,
+      payer,
+      marketPda(program, seed, gameId),
+      owner
+    );
+  }
+}
+
+/**
+ * Airdrop SOL to `trader`, open its USDC account and its team token accounts
+ * on each of `gameIds`, and mint it `usdc` test USDC. Returns the trader's
+ * USDC account.
+ */
+export async function fundTrader(
+  program: Program<Sportsxchange>,
+  provider: anchor.AnchorProvider,
+  gameIds: string[],
+  usdcMint: PublicKey,
+  trader: Keypair,
+  usdc = 1_000 * 1e6
+): Promise<PublicKey> {
+  const payer = (provider.wallet as anchor.Wallet).payer;
+  const sig = await provider.connection.requestAirdrop(trader.publicKey, LAMPORTS_PER_SOL);
+  await provider.connection.confirmTransaction(sig);
+
+  const usdcAccount = await createAssociatedTokenAccount(
+    provider.connection,
+    payer,
+    usdcMint,
+    trader.publicKey
+  );
+  for (const gameId of gameIds) {
+    await openTeamAccounts(program, provider, gameId, trader.publicKey);
+  }
+  await mintTo(provider.connection, payer, usdcMint, usdcAccount, payer, usdc);
+  return usdcAccount;
+}
+
+/**
+ * Token balance of `account`.
+ */
+export async function tokenBalance(
+  provider: anchor.AnchorProvider,
+  account: PublicKey
+): Promise<number> {
+  return Number((await getAccount(provider.connection, account)).amount);
+}
+
+export function sleep(ms: number): Promise<void> {
+  return new Promise((resolve) => setTimeout(resolve, ms));
+}
+
+export type TradeOptions = {
+  deadline?: anchor.BN | null;
+  accounts?: Record<string, PublicKey | null>;
+};
+
+/**
+ * `buyOnCurve` of `team` on `gameId` for `trader`, with no slippage limit.
+ * `options.accounts` adds or overrides accounts, e.g. a `position`. Returns
+ * the signed method builder so the caller picks `rpc` options.
+ */
+export function buy(
+  program: Program<Sportsxchange>,
+  gameId: string,
+  usdcMint: PublicKey,
+  trader: Keypair,
+  team: number,
+  usdcAmount: number,
+  options: TradeOptions = {}
+) {
+  const pda = (seed: string) => marketPda(program, seed, gameId);
+  const accounts = traderAccounts(program, gameId, usdcMint, trader.publicKey);
+  return program.methods
+    .buyOnCurve(team, new anchor.BN(usdcAmount), new anchor.BN(0), options.deadline ?? null)
+    .accounts({
+      buyer: trader.publicKey,
+      market: pda("market"),
+      teamAMint: pda("team_a_mint"),
+      teamBMint: pda("team_b_mint"),
+      buyerTeamAAccount: accounts.teamAAccount,
+      buyerTeamBAccount: accounts.teamBAccount,
+      buyerUsdc: accounts.usdc,
+      usdcVault: pda("usdc_vault"),
+      feeVault: pda("fee_vault"),
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      ...options.accounts,
+    } as any)
+    .signers([trader]);
+}
+
+/**
+ * `sellOnCurve` of `team` on `gameId` for `trader`, with no slippage limit.
+ * Returns the signed method builder.
+ */
+export function sell(
+  program: Program<Sportsxchange>,
+  gameId: string,
+  usdcMint: PublicKey,
+  trader: Keypair,
+  team: number,
+  tokenAmount: number,
+  options: TradeOptions = {}
+) {
+  const pda = (seed: string) => marketPda(program, seed, gameId);
+  const accounts = traderAccounts(program, gameId, usdcMint, trader.publicKey);
+  return program.methods
+    .sellOnCurve(team, new anchor.BN(tokenAmount), new anchor.BN(0), options.deadline ?? null)
+    .accounts({
+      seller: trader.publicKey,
+      market: pda("market"),
+      teamAMint: pda("team_a_mint"),
+      teamBMint: pda("team_b_mint"),
+      sellerTeamAAccount: accounts.teamAAccount,
+      sellerTeamBAccount: accounts.teamBAccount,
+      sellerUsdc: accounts.usdc,
+      usdcVault: pda("usdc_vault"),
+      feeVault: pda("fee_vault"),
+      tokenProgram: TOKEN_PROGRAM_ID,
+      ...options.accounts,
+    } as any)
+    .signers([trader]);
+}
+
+/**
+ * `trader`'s holder accounts on `gameId`, as taken by the claim and
+ * complete-set instructions.
+ */
+export function holderAccounts(
+  program: Program<Sportsxchange>,
+  gameId: string,
+  usdcMint: PublicKey,
+  trader: PublicKey
+) {
+  const pda = (seed: string) => marketPda(program, seed, gameId);
+  const accounts = traderAccounts(program, gameId, usdcMint, trader);
+  return {
+    user: trader,
+    market: pda("market"),
+    teamAMint: pda("team_a_mint"),
+    teamBMint: pda("team_b_mint"),
+    userTeamAAccount: accounts.teamAAccount,
+    userTeamBAccount: accounts.teamBAccount,
+    userUsdc: accounts.usdc,
+    usdcVault: pda("usdc_vault"),
+    tokenProgram: TOKEN_PROGRAM_ID,
+  };
+}
+
+/**
+ * `claimWinnings` on `gameId` for `trader`. Returns the signed method builder.
+ */
+export function claim(
+  program: Program<Sportsxchange>,
+  gameId: string,
+  usdcMint: PublicKey,
+  trader: Keypair
+) {
+  return program.methods
+    .claimWinnings()
+    .accounts(holderAccounts(program, gameId, usdcMint, trader.publicKey) as any)
+    .signers([trader]);
+}
+
+/**
+ * `claimRefund` on a voided `gameId` for `trader`. Returns the signed method
+ * builder.
+ */
+export function claimRefund(
+  program: Program<Sportsxchange>,
+  gameId: string,
+  usdcMint: PublicKey,
+  trader: Keypair
+) {
+  return program.methods
+    .claimRefund()
+    .accounts(holderAccounts(program, gameId, usdcMint, trader.publicKey) as any)
+    .signers([trader]);
+}
+
+/**
+ * Assert that `tx` fails with an error mentioning any of `errors`.
+ */
+export async function expectError(tx: Promise<unknown>, ...errors: string[]): Promise<void> {
+  try {
+    await tx;
+  } catch (err: any) {
+    const message = err.toString();
+    assert.isTrue(
+      errors.some((e) => message.includes(e)),
+      `Expected one of [${errors.join(", ")}], got: ${message}`
+    );
+    return;
+  }
+  assert.fail("Should have thrown error");
+}
@@ -0,0 +1,165 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program, BN } from "@coral-xyz/anchor";
+import { Sportsxchange } from "../target/types/sportsxchange";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import {
+  createAssociatedTokenAccount,
+  getAssociatedTokenAddress,
+  mintTo,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import {
+  buy,
+  claim,
+  createMarket,
+  eventsOf,
+  expectError,
+  fundTrader,
+  getUsdcMint,
+  marketPda,
+  tokenBalance,
+  traderAccounts,
+} from "./helpers";
+
+describe("sportsxchange - LMSR Markets", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Sportsxchange as Program<Sportsxchange>;
+  const authority = provider.wallet as anchor.Wallet;
+
+  const gameId = "2024-WEEK9-GB-DET";
+  const liquidity = 100 * 1e6; // b = 100 USDC
+  const subsidy = Math.ceil(liquidity * Math.LN2);
+  const lmsrMarket = {
+    teamA: "GB",
+    teamB: "DET",
+    basePrice: 0,
+    slope: 0,
+    curveType: { lmsr: { liquidity: new BN(liquidity) } },
+  };
+
+  let usdcMint: PublicKey;
+  let authorityUsdc: PublicKey;
+  let alice: Keypair;
+  let bob: Keypair;
+
+  const pda = (seed: string) => marketPda(program, seed, gameId);
+
+  console.log("\n" + "=".repeat(80));
+  console.log("⚖️  SportsXchange LMSR Market Tests");
+  console.log("=".repeat(80));
+
+  before(async () => {
+    usdcMint = await getUsdcMint(program, provider);
+
+    authorityUsdc = await getAssociatedTokenAddress(usdcMint, authority.publicKey);
+    if (!(await provider.connection.getAccountInfo(authorityUsdc))) {
+      authorityUsdc = await createAssociatedTokenAccount(
+        provider.connection,
+        authority.payer,
+        usdcMint,
+        authority.publicKey
+      );
+    }
+    await mintTo(
+      provider.connection,
+      authority.payer,
+      usdcMint,
+      authorityUsdc,
+      authority.payer,
+      1_000 * 1e6
+    );
+  });
+
+  it("Requires the creator's USDC account to fund the subsidy", async () => {
+    await expectError(
+      createMarket(program, "2024-LMSR-NO-SUBSIDY", usdcMint, {
+        ...lmsrMarket,
+        accounts: { authorityUsdc: null },
+      }).rpc(),
+      "SubsidyAccountRequired"
+    );
+  });
+
+  it("Seeds the pool with b * ln 2 from the creator", async () => {
+    const before = await tokenBalance(provider, authorityUsdc);
+
+    await createMarket(program, gameId, usdcMint, {
+      ...lmsrMarket,
+      accounts: { authorityUsdc },
+    }).rpc();
+
+    const market = await program.account.marketV2.fetch(pda("market"));
+    assert.strictEqual(market.subsidy.toNumber(), subsidy);
+    assert.strictEqual(market.poolValue.toNumber(), subsidy);
+    assert.strictEqual(await tokenBalance(provider, pda("usdc_vault")), subsidy);
+    assert.strictEqual(before - (await tokenBalance(provider, authorityUsdc)), subsidy);
+
+    alice = Keypair.generate();
+    bob = Keypair.generate();
+    await fundTrader(program, provider, [gameId], usdcMint, alice);
+    await fundTrader(program, provider, [gameId], usdcMint, bob);
+  });
+
+  it("Moves both prices when one side is bought", async () => {
+    const sig = await buy(program, gameId, usdcMint, alice, 0, 40 * 1e6).rpc({
+      commitment: "confirmed",
+    });
+    const [aliceTrade] = await eventsOf(program, provider, sig);
+    assert.strictEqual(aliceTrade.data.priceBefore.toNumber(), 500_000);
+    assert.isAbove(aliceTrade.data.priceAfter.toNumber(), 500_000);
+
+    // Team B got cheaper without anyone trading it
+    const bobSig = await buy(program, gameId, usdcMint, bob, 1, 10 * 1e6).rpc({
+      commitment: "confirmed",
+    });
+    const [bobTrade] = await eventsOf(program, provider, bobSig);
+    const priceB = bobTrade.data.priceBefore.toNumber();
+    assert.approximately(aliceTrade.data.priceAfter.toNumber() + priceB, 1e6, 2);
+  });
+
+  it("Pays one USDC per winning token and returns the rest of the subsidy", async () => {
+    await program.methods
+      .haltTrading()
+      .accounts({ authority: authority.publicKey, market: pda("market") } as any)
+      .rpc();
+    await program.methods
+      .resolveMarket(0)
+      .accounts({ resolver: authority.publicKey, market: pda("market") } as any)
+      .rpc();
+
+    const resolved = await program.account.marketV2.fetch(pda("market"));
+    const aliceAccounts = traderAccounts(program, gameId, usdcMint, alice.publicKey);
+    const aliceTokens = await tokenBalance(provider, aliceAccounts.teamAAccount);
+    assert.strictEqual(resolved.teamAPool.toNumber(), resolved.teamASupply.toNumber());
+    assert.strictEqual(resolved.teamBPool.toNumber(), 0);
+
+    const usdcBefore = await tokenBalance(provider, aliceAccounts.usdc);
+    await claim(program, gameId, usdcMint, alice).rpc();
+    const paid = (await tokenBalance(provider, aliceAccounts.usdc)) - usdcBefore;
+    assert.strictEqual(paid, aliceTokens);
+
+    const surplus = resolved.subsidySurplus.toNumber();
+    assert.isAtMost(surplus, subsidy + 50 * 1e6);
+    const before = await tokenBalance(provider, authorityUsdc);
+    const sig = await program.methods
+      .withdrawSubsidy()
+      .accounts({
+        authority: authority.publicKey,
+        market: pda("market"),
+        usdcVault: pda("usdc_vault"),
+        destination: authorityUsdc,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      } as any)
+      .rpc({ commitment: "confirmed" });
+
+    assert.strictEqual((await tokenBalance(provider, authorityUsdc)) - before, surplus);
+    assert.strictEqual(await tokenBalance(provider, pda("usdc_vault")), 0);
+
+    const [event] = await eventsOf(program, provider, sig);
+    assert.strictEqual(event.name, "subsidyWithdrawnEvent");
+    assert.strictEqual(event.data.amount.toNumber(), surplus);
+  });
+});
@@ -0,0 +1,144 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program, BN } from "@coral-xyz/anchor";
+import { Sportsxchange } from "../target/types/sportsxchange";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { getAccount } from "@solana/spl-token";
+import { assert } from "chai";
+import {
+  buy,
+  claim,
+  createMarket,
+  eventsOf,
+  expectError,
+  fundTrader,
+  getUsdcMint,
+  marketPda,
+  traderAccounts,
+} from "./helpers";
+
+describe("sportsxchange - Market Lifecycle", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Sportsxchange as Program<Sportsxchange>;
+  const authority = provider.wallet as anchor.Wallet;
+
+  const gameId = "2024-WEEK3-PHI-NYG";
+  const teamA = "PHI";
+  const teamB = "NYG";
+
+  const basePrice = new BN(100_000); // 0.1 USDC
+  const slope = new BN(10_000);
+
+  let usdcMint: PublicKey;
+  let marketAddress: PublicKey;
+  let usdcVault: PublicKey;
+
+  let winner: Keypair;
+  let loser: Keypair;
+
+  console.log("\n" + "=".repeat(80));
+  console.log("🏁 SportsXchange Market Lifecycle Tests");
+  console.log("=".repeat(80));
+
+  before(async () => {
+    console.log("\n🔧 Setup: Creating USDC mint and market...");
+
+    usdcMint = await getUsdcMint(program, provider);
+
+    marketAddress = marketPda(program, "market", gameId);
+    usdcVault = marketPda(program, "usdc_vault", gameId);
+
+    await createMarket(program, gameId, usdcMint, { teamA, teamB, basePrice, slope }).rpc();
+
+    winner = Keypair.generate();
+    loser = Keypair.generate();
+    await fundTrader(program, provider, [gameId], usdcMint, winner);
+    await fundTrader(program, provider, [gameId], usdcMint, loser);
+
+    await buy(program, gameId, usdcMint, winner, 0, 10 * 1e6).rpc();
+    await buy(program, gameId, usdcMint, loser, 1, 20 * 1e6).rpc();
+
+    console.log("   ✅ Market created and both sides bought");
+  });
+
+  it("Rejects resolution while trading is open", async () => {
+    await expectError(
+      program.methods
+        .resolveMarket(0)
+        .accounts({ resolver: authority.publicKey, market: marketAddress } as any)
+        .rpc(),
+      "TradingNotHalted"
+    );
+  });
+
+  it("Rejects halt from a non-authority signer", async () => {
+    await expectError(
+      program.methods
+        .haltTrading()
+        .accounts({ authority: loser.publicKey, market: marketAddress } as any)
+        .signers([loser])
+        .rpc(),
+      "Unauthorized"
+    );
+  });
+
+  it("Halts trading and blocks further buys", async () => {
+    await program.methods
+      .haltTrading()
+      .accounts({ authority: authority.publicKey, market: marketAddress } as any)
+      .rpc();
+
+    const market = await program.account.marketV2.fetch(marketAddress);
+    assert.isTrue(market.tradingHalted);
+
+    await expectError(buy(program, gameId, usdcMint, loser, 1, 1e6).rpc(), "TradingHalted");
+  });
+
+  it("Rejects claims before resolution", async () => {
+    await expectError(claim(program, gameId, usdcMint, winner).rpc(), "MarketNotResolved");
+  });
+
+  it("Resolves market with team A as winner", async () => {
+    const sig = await program.methods
+      .resolveMarket(0)
+      .accounts({ resolver: authority.publicKey, market: marketAddress } as any)
+      .rpc({ commitment: "confirmed" });
+
+    const market = await program.account.marketV2.fetch(marketAddress);
+    assert.isTrue(market.isResolved);
+    assert.strictEqual(market.winner, 0);
+
+    const [event] = await eventsOf(program, provider, sig);
+    assert.strictEqual(event.name, "marketResolvedEvent");
+    assert.strictEqual(event.data.version, 1);
+    assert.strictEqual(event.data.winner, 0);
+    assert.isTrue(event.data.teamASupply.eq(market.teamASupply));
+    assert.isTrue(event.data.poolValue.eq(market.poolValue));
+    assert.isAbove(event.data.slot.toNumber(), 0);
+  });
+
+  it("Pays the whole pool to the only winning holder", async () => {
+    const accounts = traderAccounts(program, gameId, usdcMint, winner.publicKey);
+    const before = await getAccount(provider.connection, accounts.usdc);
+    const vaultBefore = await getAccount(provider.connection, usdcVault);
+
+    await claim(program, gameId, usdcMint, winner).rpc();
+
+    const after = await getAccount(provider.connection, accounts.usdc);
+    const teamA = await getAccount(provider.connection, accounts.teamAAccount);
+    const market = await program.account.marketV2.fetch(marketAddress);
+
+    const claimed = Number(after.amount) - Number(before.amount);
+    console.log("   💰 Claimed:", (claimed / 1e6).toFixed(2), "USDC");
+
+    assert.strictEqual(claimed, Number(vaultBefore.amount));
+    assert.strictEqual(Number(teamA.amount), 0);
+    assert.strictEqual(market.teamASupply.toNumber(), 0);
+    assert.strictEqual(market.poolValue.toNumber(), 0);
+  });
+
+  it("Rejects claims from losing holders", async () => {
+    await expectError(claim(program, gameId, usdcMint, loser).rpc(), "NoWinningTokens");
+  });
+});
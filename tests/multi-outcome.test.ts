@@ -0,0 +1,244 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program, BN } from "@coral-xyz/anchor";
+import { Sportsxchange } from "../target/types/sportsxchange";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import {
+  createAssociatedTokenAccount,
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import {
+  eventsOf,
+  expectError,
+  fundTrader,
+  getUsdcMint,
+  marketPda,
+  tokenBalance,
+} from "./helpers";
+
+describe("sportsxchange - Multi-Outcome Markets", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Sportsxchange as Program<Sportsxchange>;
+  const authority = provider.wallet as anchor.Wallet;
+
+  const gameId = "2024-MASTERS-WINNER";
+  const golfers = ["SCHEFFLER", "MCILROY", "RAHM"];
+
+  let usdcMint: PublicKey;
+  let alice: Keypair;
+  let bob: Keypair;
+
+  const pda = (seed: string) => marketPda(program, seed, gameId);
+
+  const outcomeMint = (index: number) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("outcome_mint"), Buffer.from(gameId), Buffer.from([index])],
+      program.programId
+    )[0];
+
+  const outcomeAccount = (trader: Keypair, index: number) =>
+    anchor.utils.token.associatedAddress({
+      mint: outcomeMint(index),
+      owner: trader.publicKey,
+    });
+
+  const usdcAccount = (trader: Keypair) =>
+    anchor.utils.token.associatedAddress({ mint: usdcMint, owner: trader.publicKey });
+
+  const addOutcome = (name: string) =>
+    program.methods
+      .addOutcome(name)
+      .accounts({ authority: authority.publicKey, market: pda("multi_market") } as any)
+      .rpc();
+
+  const buy = (trader: Keypair, outcome: number, usdcAmount: number) =>
+    program.methods
+      .buyOutcome(outcome, new BN(usdcAmount), new BN(0), null)
+      .accounts({
+        buyer: trader.publicKey,
+        market: pda("multi_market"),
+        outcomeMint: outcomeMint(outcome),
+        buyerOutcomeAccount: outcomeAccount(trader, outcome),
+        buyerUsdc: usdcAccount(trader),
+        usdcVault: pda("multi_vault"),
+        feeVault: pda("multi_fee_vault"),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      } as any)
+      .signers([trader])
+      .rpc({ commitment: "confirmed" });
+
+  const claim = (trader: Keypair, outcome: number) =>
+    program.methods
+      .claimMultiWinnings()
+      .accounts({
+        user: trader.publicKey,
+        market: pda("multi_market"),
+        outcomeMint: outcomeMint(outcome),
+        userOutcomeAccount: outcomeAccount(trader, outcome),
+        userUsdc: usdcAccount(trader),
+        usdcVault: pda("multi_vault"),
+        tokenProgram: TOKEN_PROGRAM_ID,
+      } as any)
+      .signers([trader])
+      .rpc();
+
+  async function openOutcomeAccount(trader: Keypair, index: number) {
+    await createAssociatedTokenAccount(
+      provider.connection,
+      authority.payer,
+      outcomeMint(index),
+      trader.publicKey
+    );
+  }
+
+  console.log("\n" + "=".repeat(80));
+  console.log("🏌️  SportsXchange Multi-Outcome Market Tests");
+  console.log("=".repeat(80));
+
+  before(async () => {
+    usdcMint = await getUsdcMint(program, provider);
+
+    await program.methods
+      .createMultiMarket(gameId, new BN(100_000), new BN(10_000))
+      .accounts({ authority: authority.publicKey, usdcMint } as any)
+      .rpc();
+
+    alice = Keypair.generate();
+    bob = Keypair.generate();
+    await fundTrader(program, provider, [], usdcMint, alice);
+    await fundTrader(program, provider, [], usdcMint, bob);
+  });
+
+  it("Rejects a curve that cannot price trades", async () => {
+    await expectError(
+      program.methods
+        .createMultiMarket("2024-MASTERS-ZERO", new BN(0), new BN(0))
+        .accounts({ authority: authority.publicKey, usdcMint } as any)
+        .rpc(),
+      "InvalidCurveParams"
+    );
+  });
+
+  it("Refuses trades until the market has two outcomes", async () => {
+    await addOutcome(golfers[0]);
+    await openOutcomeAccount(bob, 0);
+
+    await expectError(buy(bob, 0, 1e6), "TooFewOutcomes");
+  });
+
+  it("Adds outcomes with their own mints", async () => {
+    for (const name of golfers.slice(1)) {
+      await addOutcome(name);
+    }
+
+    const market = await program.account.multiMarket.fetch(pda("multi_market"));
+    assert.strictEqual(market.outcomes.length, golfers.length);
+    market.outcomes.forEach((outcome: any, i: number) => {
+      assert.strictEqual(outcome.name, golfers[i]);
+      assert.isTrue(outcome.mint.equals(outcomeMint(i)));
+    });
+
+    await openOutcomeAccount(alice, 2);
+    await openOutcomeAccount(bob, 2);
+  });
+
+  it("Prices each outcome on its own curve", async () => {
+    const sig = await buy(alice, 2, 50 * 1e6);
+    await buy(bob, 0, 20 * 1e6);
+    await buy(bob, 2, 10 * 1e6);
+
+    const market = await program.account.multiMarket.fetch(pda("multi_market"));
+    const pools = market.outcomes.map((o: any) => o.pool.toNumber());
+    assert.strictEqual(market.outcomes[1].supply.toNumber(), 0);
+    assert.strictEqual(
+      pools.reduce((a: number, b: number) => a + b, 0),
+      market.poolValue.toNumber()
+    );
+    assert.strictEqual(
+      await tokenBalance(provider, pda("multi_vault")),
+      market.poolValue.toNumber()
+    );
+
+    const [event] = await eventsOf(program, provider, sig);
+    assert.strictEqual(event.name, "outcomeTradeEvent");
+    assert.strictEqual(event.data.outcome, 2);
+  });
+
+  it("Locks the outcome list once trading has started", async () => {
+    await expectError(addOutcome("SPIETH"), "OutcomesLocked");
+  });
+
+  it("Sells back into the outcome's curve", async () => {
+    const before = await tokenBalance(provider, outcomeAccount(bob, 0));
+
+    await program.methods
+      .sellOutcome(0, new BN(Math.floor(before / 2)), new BN(0), null)
+      .accounts({
+        seller: bob.publicKey,
+        market: pda("multi_market"),
+        outcomeMint: outcomeMint(0),
+        sellerOutcomeAccount: outcomeAccount(bob, 0),
+        sellerUsdc: usdcAccount(bob),
+        usdcVault: pda("multi_vault"),
+        feeVault: pda("multi_fee_vault"),
+        tokenProgram: TOKEN_PROGRAM_ID,
+      } as any)
+      .signers([bob])
+      .rpc();
+
+    const market = await program.account.multiMarket.fetch(pda("multi_market"));
+    assert.strictEqual(
+      market.outcomes[0].supply.toNumber(),
+      before - Math.floor(before / 2)
+    );
+  });
+
+  it("Rejects a winner index past the last outcome", async () => {
+    await program.methods
+      .haltMultiMarket()
+      .accounts({ authority: authority.publicKey, market: pda("multi_market") } as any)
+      .rpc();
+
+    await expectError(
+      program.methods
+        .resolveMultiMarket(golfers.length)
+        .accounts({ resolver: authority.publicKey, market: pda("multi_market") } as any)
+        .rpc(),
+      "InvalidOutcome"
+    );
+  });
+
+  it("Rejects a winner nobody holds", async () => {
+    await expectError(
+      program.methods
+        .resolveMultiMarket(1)
+        .accounts({ resolver: authority.publicKey, market: pda("multi_market") } as any)
+        .rpc(),
+      "WinnerHasNoHolders"
+    );
+  });
+
+  it("Pays the whole pool to holders of the winning outcome", async () => {
+    await program.methods
+      .resolveMultiMarket(2)
+      .accounts({ resolver: authority.publicKey, market: pda("multi_market") } as any)
+      .rpc();
+
+    await claim(alice, 2);
+    await claim(bob, 2);
+
+    const market = await program.account.multiMarket.fetch(pda("multi_market"));
+    assert.strictEqual(market.winner, 2);
+    assert.strictEqual(market.outcomes[2].supply.toNumber(), 0);
+    assert.strictEqual(market.poolValue.toNumber(), 0);
+    assert.strictEqual(await tokenBalance(provider, pda("multi_vault")), 0);
+  });
+
+  it("Only accepts the winning outcome's mint on claim", async () => {
+    await expectError(claim(bob, 0), "ConstraintSeeds");
+  });
+});
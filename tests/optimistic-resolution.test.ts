@@ -0,0 +1,271 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program, BN } from "@coral-xyz/anchor";
+import { Sportsxchange } from "../target/types/sportsxchange";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import {
+  createAssociatedTokenAccount,
+  getAssociatedTokenAddress,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import {
+  buy,
+  claim,
+  closeTs,
+  createMarket,
+  eventsOf,
+  expectError,
+  fundTrader,
+  getUsdcMint,
+  marketPda,
+  openTeamAccounts,
+  sleep,
+  tokenBalance,
+} from "./helpers";
+
+describe("sportsxchange - Optimistic Resolution", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Sportsxchange as Program<Sportsxchange>;
+  const authority = provider.wallet as anchor.Wallet;
+
+  const undisputedGame = "2024-WEEK15-BUF-MIA";
+  const disputedGame = "2024-WEEK15-DAL-PHI";
+  const cancelledGame = "2024-WEEK15-NYG-WAS";
+  const bond = 10 * 1e6;
+
+  let usdcMint: PublicKey;
+  let authorityUsdc: PublicKey;
+
+  type Wallet = { keypair: Keypair; usdc: PublicKey };
+  let proposer: Wallet;
+  let disputer: Wallet;
+  let holder: Wallet;
+
+  const resolutionPda = (seed: string, gameId: string) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from(seed), marketPda(program, "market", gameId).toBuffer()],
+      program.programId
+    )[0];
+
+  async function newWallet(): Promise<Wallet> {
+    const keypair = Keypair.generate();
+    const usdc = await fundTrader(program, provider, [], usdcMint, keypair, 100 * 1e6);
+    return { keypair, usdc };
+  }
+
+  const setResolutionParams = (resolutionBond: number, disputeWindowSecs: number) =>
+    program.methods
+      .setResolutionParams(new BN(resolutionBond), new BN(disputeWindowSecs))
+      .accounts({ admin: authority.publicKey } as any)
+      .rpc();
+
+  const propose = (gameId: string, outcome: number) =>
+    program.methods
+      .proposeOutcome(outcome)
+      .accounts({
+        proposer: proposer.keypair.publicKey,
+        market: marketPda(program, "market", gameId),
+        proposal: resolutionPda("proposal", gameId),
+        bondVault: resolutionPda("bond_vault", gameId),
+        proposerUsdc: proposer.usdc,
+        usdcMint,
+      } as any)
+      .signers([proposer.keypair])
+      .rpc({ commitment: "confirmed" });
+
+  const dispute = (gameId: string) =>
+    program.methods
+      .disputeOutcome()
+      .accounts({
+        disputer: disputer.keypair.publicKey,
+        market: marketPda(program, "market", gameId),
+        proposal: resolutionPda("proposal", gameId),
+        bondVault: resolutionPda("bond_vault", gameId),
+        disputerUsdc: disputer.usdc,
+      } as any)
+      .signers([disputer.keypair])
+      .rpc({ commitment: "confirmed" });
+
+  const finalize = (gameId: string) =>
+    program.methods
+      .finalizeOutcome()
+      .accounts({
+        market: marketPda(program, "market", gameId),
+        proposal: resolutionPda("proposal", gameId),
+        proposer: proposer.keypair.publicKey,
+        bondVault: resolutionPda("bond_vault", gameId),
+        proposerUsdc: proposer.usdc,
+      } as any)
+      .rpc({ commitment: "confirmed" });
+
+  const arbitrate = (gameId: string, outcome: number, arbiter: Keypair, arbiterUsdc: PublicKey) =>
+    program.methods
+      .arbitrateOutcome(outcome)
+      .accounts({
+        arbiter: arbiter.publicKey,
+        market: marketPda(program, "market", gameId),
+        proposal: resolutionPda("proposal", gameId),
+        proposer: proposer.keypair.publicKey,
+        bondVault: resolutionPda("bond_vault", gameId),
+        arbiterUsdc,
+      } as any)
+      .signers([arbiter])
+      .rpc({ commitment: "confirmed" });
+
+  console.log("\n" + "=".repeat(80));
+  console.log("⚖️  SportsXchange Optimistic Resolution Tests");
+  console.log("=".repeat(80));
+
+  before(async () => {
+    usdcMint = await getUsdcMint(program, provider);
+    authorityUsdc = await getAssociatedTokenAddress(usdcMint, authority.publicKey);
+    if (!(await provider.connection.getAccountInfo(authorityUsdc))) {
+      authorityUsdc = await createAssociatedTokenAccount(
+        provider.connection,
+        authority.payer,
+        usdcMint,
+        authority.publicKey
+      );
+    }
+    await setResolutionParams(bond, 3);
+
+    proposer = await newWallet();
+    disputer = await newWallet();
+    holder = await newWallet();
+
+    for (const gameId of [undisputedGame, disputedGame, cancelledGame]) {
+      await createMarket(program, gameId, usdcMint, { tradingCloseTs: closeTs(4) }).rpc();
+    }
+
+    await openTeamAccounts(program, provider, undisputedGame, holder.keypair.publicKey);
+    await buy(program, undisputedGame, usdcMint, holder.keypair, 0, 5 * 1e6).rpc();
+
+    await sleep(5_000);
+  });
+
+  after(async () => {
+    // Leave the singleton config the way other suites expect it
+    await setResolutionParams(100 * 1e6, 2 * 60 * 60);
+  });
+
+  it("Escrows the proposer's bond", async () => {
+    const sig = await propose(undisputedGame, 0);
+
+    assert.strictEqual(await tokenBalance(provider, proposer.usdc), 100 * 1e6 - bond);
+    const bondVault = resolutionPda("bond_vault", undisputedGame);
+    assert.strictEqual(await tokenBalance(provider, bondVault), bond);
+
+    const market = await program.account.marketV2.fetch(
+      marketPda(program, "market", undisputedGame)
+    );
+    assert.deepEqual(Object.keys(market.resolutionState), ["proposed"]);
+    assert.isFalse(market.isResolved);
+
+    const [event] = await eventsOf(program, provider, sig);
+    assert.strictEqual(event.name, "resolutionEvent");
+    assert.deepEqual(Object.keys(event.data.action), ["proposed"]);
+  });
+
+  it("Holds claims and direct resolution until finalization", async () => {
+    await expectError(
+      claim(program, undisputedGame, usdcMint, holder.keypair).rpc(),
+      "MarketNotResolved"
+    );
+    await expectError(
+      program.methods
+        .resolveMarket(1)
+        .accounts({
+          resolver: authority.publicKey,
+          market: marketPda(program, "market", undisputedGame),
+        } as any)
+        .rpc(),
+      "ProposalPending"
+    );
+    await expectError(finalize(undisputedGame), "DisputeWindowOpen");
+  });
+
+  it("Finalizes an undisputed proposal and returns the bond", async () => {
+    await sleep(4_000);
+    await expectError(dispute(undisputedGame), "DisputeWindowClosed");
+
+    await finalize(undisputedGame);
+
+    const market = await program.account.marketV2.fetch(
+      marketPda(program, "market", undisputedGame)
+    );
+    assert.isTrue(market.isResolved);
+    assert.strictEqual(market.winner, 0);
+    assert.strictEqual(await tokenBalance(provider, proposer.usdc), 100 * 1e6);
+
+    const before = await tokenBalance(provider, holder.usdc);
+    await claim(program, undisputedGame, usdcMint, holder.keypair).rpc();
+    assert.isAbove(await tokenBalance(provider, holder.usdc), before);
+  });
+
+  it("Escalates a disputed proposal to the arbiter", async () => {
+    await propose(disputedGame, 1);
+    await dispute(disputedGame);
+
+    assert.strictEqual(await tokenBalance(provider, disputer.usdc), 100 * 1e6 - bond);
+    const market = await program.account.marketV2.fetch(marketPda(program, "market", disputedGame));
+    assert.deepEqual(Object.keys(market.resolutionState), ["disputed"]);
+
+    await sleep(4_000);
+    await expectError(finalize(disputedGame), "NoProposal");
+    await expectError(arbitrate(disputedGame, 0, holder.keypair, holder.usdc), "ConstraintHasOne");
+  });
+
+  it("Lets the arbiter rule and keep both bonds", async () => {
+    const before = await tokenBalance(provider, authorityUsdc);
+    const sig = await arbitrate(disputedGame, 0, authority.payer, authorityUsdc);
+
+    assert.strictEqual(await tokenBalance(provider, authorityUsdc), before + 2 * bond);
+
+    const market = await program.account.marketV2.fetch(marketPda(program, "market", disputedGame));
+    assert.isTrue(market.isResolved);
+    assert.strictEqual(market.winner, 0);
+    assert.deepEqual(Object.keys(market.resolutionState), ["idle"]);
+
+    const events = await eventsOf(program, provider, sig);
+    assert.deepEqual(
+      events.map((e) => e.name),
+      ["resolutionEvent", "marketResolvedEvent"]
+    );
+    assert.isTrue(events[1].data.resolver.equals(authority.publicKey));
+  });
+
+  it("Lets the arbiter void a disputed market whose game was cancelled", async () => {
+    await propose(cancelledGame, 0);
+    await dispute(cancelledGame);
+
+    const before = await tokenBalance(provider, authorityUsdc);
+    const sig = await program.methods
+      .arbitrateVoid()
+      .accounts({
+        arbiter: authority.publicKey,
+        market: marketPda(program, "market", cancelledGame),
+        proposal: resolutionPda("proposal", cancelledGame),
+        proposer: proposer.keypair.publicKey,
+        bondVault: resolutionPda("bond_vault", cancelledGame),
+        arbiterUsdc: authorityUsdc,
+      } as any)
+      .rpc({ commitment: "confirmed" });
+
+    assert.strictEqual(await tokenBalance(provider, authorityUsdc), before + 2 * bond);
+
+    const market = await program.account.marketV2.fetch(
+      marketPda(program, "market", cancelledGame)
+    );
+    assert.isTrue(market.isVoided);
+    assert.isFalse(market.isResolved);
+    assert.deepEqual(Object.keys(market.resolutionState), ["idle"]);
+
+    const events = await eventsOf(program, provider, sig);
+    assert.deepEqual(
+      events.map((e) => e.name),
+      ["resolutionEvent", "marketVoidedEvent"]
+    );
+    assert.deepEqual(Object.keys(events[0].data.action), ["voided"]);
+  });
+});
@@ -0,0 +1,122 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Sportsxchange } from "../target/types/sportsxchange";
+import { PublicKey, Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { assert } from "chai";
+import {
+  closeTs,
+  createMarket,
+  eventsOf,
+  expectError,
+  getUsdcMint,
+  marketPda,
+  sleep,
+} from "./helpers";
+
+describe("sportsxchange - Oracle Resolution", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Sportsxchange as Program<Sportsxchange>;
+  const authority = provider.wallet as anchor.Wallet;
+
+  const gameId = "2024-WEEK14-GB-CHI";
+
+  // Local test publishers standing in for the real feed
+  const publisher = Keypair.generate();
+  const impostor = Keypair.generate();
+
+  const pda = (seed: string) => marketPda(program, seed, gameId);
+
+  const feedPda = (feedPublisher: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("oracle_result"), feedPublisher.toBuffer(), Buffer.from(gameId)],
+      program.programId
+    )[0];
+
+  const publish = (signer: Keypair, home: number, away: number, status: any) =>
+    program.methods
+      .publishOracleResult(gameId, home, away, status)
+      .accounts({ publisher: signer.publicKey } as any)
+      .signers([signer])
+      .rpc({ commitment: "confirmed" });
+
+  const update = (home: number, away: number, status: any) =>
+    program.methods
+      .updateOracleResult(home, away, status)
+      .accounts({ publisher: publisher.publicKey, oracleResult: feedPda(publisher.publicKey) } as any)
+      .signers([publisher])
+      .rpc({ commitment: "confirmed" });
+
+  const resolve = (feed: PublicKey) =>
+    program.methods
+      .resolveFromOracle()
+      .accounts({ market: pda("market"), oracleResult: feed } as any)
+      .rpc({ commitment: "confirmed" });
+
+  console.log("\n" + "=".repeat(80));
+  console.log("📡 SportsXchange Oracle Resolution Tests");
+  console.log("=".repeat(80));
+
+  before(async () => {
+    const usdcMint = await getUsdcMint(program, provider);
+
+    for (const signer of [publisher, impostor]) {
+      const sig = await provider.connection.requestAirdrop(signer.publicKey, LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(sig);
+    }
+
+    await createMarket(program, gameId, usdcMint, {
+      teamA: "GB",
+      teamB: "CHI",
+      tradingCloseTs: closeTs(3),
+      oracle: publisher.publicKey,
+    }).rpc();
+  });
+
+  it("Stores the oracle on the market", async () => {
+    const market = await program.account.marketV2.fetch(pda("market"));
+    assert.isTrue(market.oracle.equals(publisher.publicKey));
+  });
+
+  it("Publishes a game's feed", async () => {
+    const sig = await publish(publisher, 7, 3, { inProgress: {} });
+
+    const feed = await program.account.oracleResult.fetch(feedPda(publisher.publicKey));
+    assert.strictEqual(feed.gameId, gameId);
+    assert.strictEqual(feed.homeScore, 7);
+    assert.deepEqual(Object.keys(feed.status), ["inProgress"]);
+    assert.isTrue(feed.publisher.equals(publisher.publicKey));
+    assert.isAbove(feed.timestamp.toNumber(), 0);
+
+    const [event] = await eventsOf(program, provider, sig);
+    assert.strictEqual(event.name, "oracleResultPublishedEvent");
+  });
+
+  it("Refuses to settle from an unfinished or foreign feed", async () => {
+    await sleep(5_000);
+
+    await expectError(resolve(feedPda(publisher.publicKey)), "OracleResultNotFinal");
+
+    await publish(impostor, 0, 21, { final: {} });
+    await expectError(resolve(feedPda(impostor.publicKey)), "InvalidOracle");
+  });
+
+  it("Lets anyone settle from the oracle's final result", async () => {
+    await update(24, 17, { final: {} });
+
+    const sig = await resolve(feedPda(publisher.publicKey));
+
+    const market = await program.account.marketV2.fetch(pda("market"));
+    assert.isTrue(market.isResolved);
+    assert.strictEqual(market.winner, 0);
+
+    const [event] = await eventsOf(program, provider, sig);
+    assert.strictEqual(event.name, "marketResolvedEvent");
+    assert.isTrue(event.data.resolver.equals(publisher.publicKey));
+  });
+
+  it("Freezes final results", async () => {
+    await expectError(update(24, 20, { final: {} }), "OracleResultFinal");
+  });
+});
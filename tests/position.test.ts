@@ -0,0 +1,124 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Sportsxchange } from "../target/types/sportsxchange";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { assert } from "chai";
+import {
+  buy,
+  createMarket,
+  eventsOf,
+  expectError,
+  fundTrader,
+  getUsdcMint,
+  marketPda,
+  sell,
+} from "./helpers";
+
+describe("sportsxchange - User Positions", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Sportsxchange as Program<Sportsxchange>;
+
+  const gameId = "2024-WEEK13-MIA-NYJ";
+
+  let usdcMint: PublicKey;
+
+  let alice: Keypair;
+  let bob: Keypair;
+
+  const pda = (seed: string) => marketPda(program, seed, gameId);
+
+  const positionPda = (user: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("position"), pda("market").toBuffer(), user.toBuffer()],
+      program.programId
+    )[0];
+
+  console.log("\n" + "=".repeat(80));
+  console.log("📒 SportsXchange User Position Tests");
+  console.log("=".repeat(80));
+
+  before(async () => {
+    usdcMint = await getUsdcMint(program, provider);
+
+    await createMarket(program, gameId, usdcMint, { teamA: "MIA", teamB: "NYJ" }).rpc();
+
+    alice = Keypair.generate();
+    bob = Keypair.generate();
+    for (const trader of [alice, bob]) {
+      await fundTrader(program, provider, [gameId], usdcMint, trader, 100 * 1e6);
+    }
+  });
+
+  it("Opens an empty position for a wallet", async () => {
+    await program.methods
+      .openPosition()
+      .accounts({ user: alice.publicKey, market: pda("market") } as any)
+      .signers([alice])
+      .rpc();
+
+    const position = await program.account.userPosition.fetch(positionPda(alice.publicKey));
+    assert.isTrue(position.market.equals(pda("market")));
+    assert.isTrue(position.user.equals(alice.publicKey));
+    assert.strictEqual(position.teamATokens.toNumber(), 0);
+    assert.strictEqual(position.firstTradeSlot.toNumber(), 0);
+  });
+
+  it("Records buys and sells passed the position", async () => {
+    const tracked = { accounts: { position: positionPda(alice.publicKey) } };
+    const buySig = await buy(program, gameId, usdcMint, alice, 0, 10 * 1e6, tracked).rpc({
+      commitment: "confirmed",
+    });
+    const [bought] = await eventsOf(program, provider, buySig);
+    const tokens = bought.data.tokenAmount.toNumber();
+
+    let position = await program.account.userPosition.fetch(positionPda(alice.publicKey));
+    assert.strictEqual(position.teamATokens.toNumber(), tokens);
+    assert.strictEqual(position.teamACostBasis.toNumber(), 10 * 1e6);
+    assert.strictEqual(position.usdcSpent.toNumber(), 10 * 1e6);
+    assert.isAbove(position.firstTradeSlot.toNumber(), 0);
+
+    const half = Math.floor(tokens / 2);
+    const sellSig = await sell(program, gameId, usdcMint, alice, 0, half, tracked).rpc({
+      commitment: "confirmed",
+    });
+    const [sold] = await eventsOf(program, provider, sellSig);
+    const proceeds = sold.data.usdcAmount.toNumber();
+
+    position = await program.account.userPosition.fetch(positionPda(alice.publicKey));
+    const released = Math.floor((10 * 1e6 * half) / tokens);
+    assert.strictEqual(position.teamATokens.toNumber(), tokens - half);
+    assert.strictEqual(position.teamACostBasis.toNumber(), 10 * 1e6 - released);
+    assert.strictEqual(position.usdcReceived.toNumber(), proceeds);
+    assert.strictEqual(position.realizedPnl.toNumber(), proceeds - released);
+    assert.isAtLeast(position.lastTradeSlot.toNumber(), position.firstTradeSlot.toNumber());
+  });
+
+  it("Trades without a position account as before", async () => {
+    await buy(program, gameId, usdcMint, bob, 0, 1e6, { accounts: { position: null } }).rpc();
+  });
+
+  it("Rejects another wallet's position", async () => {
+    await expectError(
+      buy(program, gameId, usdcMint, bob, 0, 1e6, {
+        accounts: { position: positionPda(alice.publicKey) },
+      }).rpc(),
+      "InvalidPosition"
+    );
+  });
+
+  it("Closes the position and refunds its rent", async () => {
+    await program.methods
+      .closePosition()
+      .accounts({
+        user: alice.publicKey,
+        position: positionPda(alice.publicKey),
+      } as any)
+      .signers([alice])
+      .rpc();
+
+    const info = await provider.connection.getAccountInfo(positionPda(alice.publicKey));
+    assert.isNull(info);
+  });
+});
@@ -0,0 +1,184 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Sportsxchange } from "../target/types/sportsxchange";
+import { PublicKey, Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { assert } from "chai";
+import {
+  buy,
+  configPda,
+  createMarket,
+  expectError,
+  fundTrader,
+  getUsdcMint,
+  marketPda,
+} from "./helpers";
+
+describe("sportsxchange - Protocol Config", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Sportsxchange as Program<Sportsxchange>;
+  const admin = provider.wallet as anchor.Wallet;
+
+  const gameId = "2024-WEEK5-DET-MIN";
+
+  let usdcMint: PublicKey;
+  let config: PublicKey;
+  let pauser: Keypair;
+  let outsider: Keypair;
+
+  const pda = (seed: string) => marketPda(program, seed, gameId);
+
+  async function airdrop(to: PublicKey) {
+    const sig = await provider.connection.requestAirdrop(to, LAMPORTS_PER_SOL);
+    await provider.connection.confirmTransaction(sig);
+  }
+
+  const updateRoles = (roles: {
+    admin?: PublicKey;
+    marketCreator?: PublicKey;
+    resolver?: PublicKey;
+    pauser?: PublicKey;
+    arbiter?: PublicKey;
+  }) =>
+    program.methods
+      .updateRoles(
+        roles.admin ?? null,
+        roles.marketCreator ?? null,
+        roles.resolver ?? null,
+        roles.pauser ?? null,
+        roles.arbiter ?? null
+      )
+      .accounts({ admin: admin.publicKey } as any)
+      .rpc();
+
+  const setPaused = (signer: Keypair, paused: boolean) =>
+    program.methods
+      .setPaused(paused)
+      .accounts({ authority: signer.publicKey } as any)
+      .signers([signer])
+      .rpc();
+
+  console.log("\n" + "=".repeat(80));
+  console.log("⚙️  SportsXchange Protocol Config Tests");
+  console.log("=".repeat(80));
+
+  before(async () => {
+    usdcMint = await getUsdcMint(program, provider);
+    config = configPda(program);
+
+    pauser = Keypair.generate();
+    outsider = Keypair.generate();
+    await airdrop(pauser.publicKey);
+    await airdrop(outsider.publicKey);
+
+    await createMarket(program, gameId, usdcMint, { teamA: "DET", teamB: "MIN" }).rpc();
+  });
+
+  after(async () => {
+    // Leave the singleton config the way other suites expect it
+    await updateRoles({
+      marketCreator: admin.publicKey,
+      resolver: admin.publicKey,
+      pauser: admin.publicKey,
+    });
+    const state = await program.account.protocolConfig.fetch(config);
+    if (state.paused) {
+      await program.methods
+        .setPaused(false)
+        .accounts({ authority: admin.publicKey } as any)
+        .rpc();
+    }
+  });
+
+  it("Rotates the pauser role", async () => {
+    await updateRoles({ pauser: pauser.publicKey });
+
+    const state = await program.account.protocolConfig.fetch(config);
+    assert.isTrue(state.pauser.equals(pauser.publicKey));
+    assert.isTrue(state.admin.equals(admin.publicKey));
+  });
+
+  it("Rejects role changes from a non-admin", async () => {
+    await expectError(
+      program.methods
+        .updateRoles(outsider.publicKey, null, null, null, null)
+        .accounts({ admin: outsider.publicKey } as any)
+        .signers([outsider])
+        .rpc(),
+      "ConstraintHasOne"
+    );
+  });
+
+  it("Rejects a pause from a key with no role", async () => {
+    await expectError(setPaused(outsider, true), "Unauthorized");
+  });
+
+  it("Pauses trading and market creation protocol-wide", async () => {
+    await setPaused(pauser, true);
+
+    await fundTrader(program, provider, [gameId], usdcMint, outsider, 10 * 1e6);
+    await expectError(
+      buy(program, gameId, usdcMint, outsider, 0, 1e6).rpc(),
+      "ProtocolPaused"
+    );
+
+    await expectError(
+      createMarket(program, "2024-WEEK5-PAUSED", usdcMint).rpc(),
+      "ProtocolPaused"
+    );
+
+    await setPaused(pauser, false);
+  });
+
+  it("Lets the pauser halt any market", async () => {
+    await program.methods
+      .haltTrading()
+      .accounts({ authority: pauser.publicKey, market: pda("market") } as any)
+      .signers([pauser])
+      .rpc();
+
+    const market = await program.account.marketV2.fetch(pda("market"));
+    assert.isTrue(market.tradingHalted);
+  });
+
+  it("Only the resolver role can resolve", async () => {
+    await updateRoles({ resolver: pauser.publicKey });
+
+    await expectError(
+      program.methods
+        .resolveMarket(1)
+        .accounts({ resolver: admin.publicKey, market: pda("market") } as any)
+        .rpc(),
+      "ConstraintHasOne"
+    );
+
+    await program.methods
+      .resolveMarket(1)
+      .accounts({ resolver: pauser.publicKey, market: pda("market") } as any)
+      .signers([pauser])
+      .rpc();
+
+    const market = await program.account.marketV2.fetch(pda("market"));
+    assert.strictEqual(market.winner, 1);
+  });
+
+  it("Only the market creator role can create markets", async () => {
+    await updateRoles({ marketCreator: outsider.publicKey });
+
+    await expectError(
+      createMarket(program, "2024-WEEK5-NOT-CREATOR", usdcMint).rpc(),
+      "Unauthorized"
+    );
+  });
+
+  it("Caps total fees", async () => {
+    await expectError(
+      program.methods
+        .setFees(800, 300)
+        .accounts({ admin: admin.publicKey } as any)
+        .rpc(),
+      "FeeTooHigh"
+    );
+  });
+});
@@ -0,0 +1,128 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program, BN } from "@coral-xyz/anchor";
+import { Sportsxchange } from "../target/types/sportsxchange";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { assert } from "chai";
+import {
+  buy,
+  claim,
+  closeTs,
+  createMarket,
+  eventsOf,
+  expectError,
+  fundTrader,
+  getUsdcMint,
+  marketPda,
+  sleep,
+  tokenBalance,
+  traderAccounts,
+} from "./helpers";
+
+describe("sportsxchange - Scalar Markets", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Sportsxchange as Program<Sportsxchange>;
+  const authority = provider.wallet as anchor.Wallet;
+
+  // Passing yards over a 200-300 range
+  const gameId = "2024-WEEK17-MAHOMES-PASS-YDS";
+  const lower = 200;
+  const upper = 300;
+
+  let usdcMint: PublicKey;
+  let long: Keypair;
+  let short: Keypair;
+
+  const pda = (seed: string) => marketPda(program, seed, gameId);
+
+  console.log("\n" + "=".repeat(80));
+  console.log("📐 SportsXchange Scalar Market Tests");
+  console.log("=".repeat(80));
+
+  before(async () => {
+    usdcMint = await getUsdcMint(program, provider);
+  });
+
+  it("Rejects an empty range", async () => {
+    await expectError(
+      createMarket(program, "2024-WEEK17-EMPTY-RANGE", usdcMint, {
+        kind: { scalar: { lower: new BN(upper), upper: new BN(upper) } },
+      }).rpc(),
+      "InvalidMarketKind"
+    );
+  });
+
+  it("Trades long and short tokens on the two-mint layout", async () => {
+    await createMarket(program, gameId, usdcMint, {
+      teamA: "LONG",
+      teamB: "SHORT",
+      kind: { scalar: { lower: new BN(lower), upper: new BN(upper) } },
+      tradingCloseTs: closeTs(4),
+    }).rpc();
+
+    long = Keypair.generate();
+    short = Keypair.generate();
+    for (const trader of [long, short]) {
+      await fundTrader(program, provider, [gameId], usdcMint, trader, 100 * 1e6);
+    }
+    await buy(program, gameId, usdcMint, long, 0, 20 * 1e6).rpc();
+    await buy(program, gameId, usdcMint, short, 1, 20 * 1e6).rpc();
+
+    const market = await program.account.marketV2.fetch(pda("market"));
+    assert.strictEqual(market.kind.scalar.upper.toNumber(), upper);
+    assert.isAbove(market.poolValue.toNumber(), 0);
+  });
+
+  it("Only settles at a reported value", async () => {
+    await sleep(5_000);
+    await expectError(
+      program.methods
+        .resolveWithScores(24, 20)
+        .accounts({ resolver: authority.publicKey, market: pda("market") } as any)
+        .rpc(),
+      "InvalidMarketKind"
+    );
+    for (const outcome of [0, 1, 2]) {
+      await expectError(
+        program.methods
+          .resolveMarket(outcome)
+          .accounts({ resolver: authority.publicKey, market: pda("market") } as any)
+          .rpc(),
+        "InvalidMarketKind"
+      );
+    }
+  });
+
+  it("Splits the pool linearly at the reported value", async () => {
+    const sig = await program.methods
+      .resolveScalar(new BN(275))
+      .accounts({ resolver: authority.publicKey, market: pda("market") } as any)
+      .rpc({ commitment: "confirmed" });
+
+    const market = await program.account.marketV2.fetch(pda("market"));
+    assert.isTrue(market.isResolved);
+    assert.strictEqual(market.scalarValue.toNumber(), 275);
+    const pool = market.poolValue.toNumber();
+    assert.strictEqual(market.teamAPool.toNumber(), Math.floor((pool * 3) / 4));
+    assert.strictEqual(market.teamBPool.toNumber(), pool - Math.floor((pool * 3) / 4));
+
+    const [event] = await eventsOf(program, provider, sig);
+    assert.strictEqual(event.name, "marketResolvedEvent");
+    assert.strictEqual(event.data.scalarValue.toNumber(), 275);
+  });
+
+  it("Pays both sides and empties the vault", async () => {
+    const before = await program.account.marketV2.fetch(pda("market"));
+    for (const trader of [long, short]) {
+      const accounts = traderAccounts(program, gameId, usdcMint, trader.publicKey);
+      const usdcBefore = await tokenBalance(provider, accounts.usdc);
+      await claim(program, gameId, usdcMint, trader).rpc();
+      const paid = (await tokenBalance(provider, accounts.usdc)) - usdcBefore;
+      const owed = trader === long ? before.teamAPool : before.teamBPool;
+      assert.strictEqual(paid, owed.toNumber());
+    }
+
+    assert.strictEqual(await tokenBalance(provider, pda("usdc_vault")), 0);
+  });
+});
@@ -0,0 +1,158 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Sportsxchange } from "../target/types/sportsxchange";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { assert } from "chai";
+import {
+  buy,
+  claimRefund,
+  closeTs,
+  createMarket,
+  eventsOf,
+  expectError,
+  fundTrader,
+  getUsdcMint,
+  marketPda,
+  sleep,
+  tokenBalance,
+  traderAccounts,
+} from "./helpers";
+
+describe("sportsxchange - Spread and Total Markets", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Sportsxchange as Program<Sportsxchange>;
+  const authority = provider.wallet as anchor.Wallet;
+
+  // KC -3.5 and over/under 47 on the same game
+  const spreadGame = "2024-WEEK16-KC-HOU-SPREAD";
+  const totalGame = "2024-WEEK16-KC-HOU-TOTAL";
+  const oneSidedGame = "2024-WEEK16-KC-HOU-ALT-SPREAD";
+
+  let usdcMint: PublicKey;
+  let over: Keypair;
+  let under: Keypair;
+
+  const resolveWithScores = (gameId: string, home: number, away: number) =>
+    program.methods
+      .resolveWithScores(home, away)
+      .accounts({
+        resolver: authority.publicKey,
+        market: marketPda(program, "market", gameId),
+      } as any)
+      .rpc({ commitment: "confirmed" });
+
+  console.log("\n" + "=".repeat(80));
+  console.log("📏 SportsXchange Spread and Total Market Tests");
+  console.log("=".repeat(80));
+
+  before(async () => {
+    usdcMint = await getUsdcMint(program, provider);
+
+    const kinds: [string, any][] = [
+      [spreadGame, { spread: { line: -7 } }],
+      [totalGame, { total: { line: 94 } }],
+      [oneSidedGame, { spread: { line: -21 } }],
+    ];
+    for (const [gameId, kind] of kinds) {
+      await createMarket(program, gameId, usdcMint, {
+        teamA: "KC",
+        teamB: "HOU",
+        kind,
+        tradingCloseTs: closeTs(4),
+      }).rpc();
+    }
+
+    over = Keypair.generate();
+    under = Keypair.generate();
+    const gameIds = [spreadGame, totalGame, oneSidedGame];
+    for (const trader of [over, under]) {
+      await fundTrader(program, provider, gameIds, usdcMint, trader, 100 * 1e6);
+    }
+
+    for (const gameId of [spreadGame, totalGame]) {
+      await buy(program, gameId, usdcMint, over, 0, 20 * 1e6).rpc();
+      await buy(program, gameId, usdcMint, under, 1, 10 * 1e6).rpc();
+    }
+    // Nobody takes the other side of KC -10.5
+    await buy(program, oneSidedGame, usdcMint, over, 0, 10 * 1e6).rpc();
+
+    await sleep(5_000);
+  });
+
+  it("Stores the line on the market", async () => {
+    const market = await program.account.marketV2.fetch(marketPda(program, "market", spreadGame));
+    assert.deepEqual(market.kind, { spread: { line: -7 } });
+  });
+
+  it("Refuses to settle a spread as a draw", async () => {
+    await expectError(
+      program.methods
+        .resolveMarket(2)
+        .accounts({
+          resolver: authority.publicKey,
+          market: marketPda(program, "market", spreadGame),
+        } as any)
+        .rpc(),
+      "InvalidMarketKind"
+    );
+  });
+
+  it("Settles a spread against the final score", async () => {
+    // A 3-point home win does not cover -3.5
+    const sig = await resolveWithScores(spreadGame, 23, 20);
+
+    const market = await program.account.marketV2.fetch(marketPda(program, "market", spreadGame));
+    assert.isTrue(market.isResolved);
+    assert.strictEqual(market.winner, 1);
+
+    const [event] = await eventsOf(program, provider, sig);
+    assert.strictEqual(event.name, "marketResolvedEvent");
+  });
+
+  it("Voids a push on a whole-number total", async () => {
+    const sig = await resolveWithScores(totalGame, 27, 20);
+
+    const market = await program.account.marketV2.fetch(marketPda(program, "market", totalGame));
+    assert.isTrue(market.isVoided);
+    assert.isFalse(market.isResolved);
+
+    const [event] = await eventsOf(program, provider, sig);
+    assert.strictEqual(event.name, "marketVoidedEvent");
+    assert.isTrue(event.data.authority.equals(authority.publicKey));
+  });
+
+  it("Refunds both sides of a push pro rata", async () => {
+    for (const trader of [over, under]) {
+      const accounts = traderAccounts(program, totalGame, usdcMint, trader.publicKey);
+      const before = await tokenBalance(provider, accounts.usdc);
+      await claimRefund(program, totalGame, usdcMint, trader).rpc();
+      assert.isAbove(await tokenBalance(provider, accounts.usdc), before);
+    }
+
+    const market = await program.account.marketV2.fetch(marketPda(program, "market", totalGame));
+    assert.strictEqual(market.poolValue.toNumber(), 0);
+    const vault = marketPda(program, "usdc_vault", totalGame);
+    assert.strictEqual(await tokenBalance(provider, vault), 0);
+  });
+
+  it("Voids a line nobody on the winning side holds", async () => {
+    // A 3-point home win does not cover -10.5, and no one bought team B
+    const sig = await resolveWithScores(oneSidedGame, 23, 20);
+
+    const market = await program.account.marketV2.fetch(marketPda(program, "market", oneSidedGame));
+    assert.isTrue(market.isVoided);
+    assert.isFalse(market.isResolved);
+
+    const [event] = await eventsOf(program, provider, sig);
+    assert.strictEqual(event.name, "marketVoidedEvent");
+
+    const accounts = traderAccounts(program, oneSidedGame, usdcMint, over.publicKey);
+    const before = await tokenBalance(provider, accounts.usdc);
+    await claimRefund(program, oneSidedGame, usdcMint, over).rpc();
+    assert.isAbove(await tokenBalance(provider, accounts.usdc), before);
+    const vault = marketPda(program, "usdc_vault", oneSidedGame);
+    assert.strictEqual(await tokenBalance(provider, vault), 0);
+  });
+});
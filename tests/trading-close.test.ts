@@ -0,0 +1,104 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program, BN } from "@coral-xyz/anchor";
+import { Sportsxchange } from "../target/types/sportsxchange";
+import { PublicKey, Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { assert } from "chai";
+import {
+  buy,
+  closeTs,
+  createMarket,
+  eventsOf,
+  expectError,
+  fundTrader,
+  getUsdcMint,
+  marketPda,
+  openTeamAccounts,
+  sleep,
+} from "./helpers";
+
+describe("sportsxchange - Trading Close", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Sportsxchange as Program<Sportsxchange>;
+  const authority = provider.wallet as anchor.Wallet;
+
+  const gameId = "2024-WEEK12-CHI-DET";
+
+  let usdcMint: PublicKey;
+  let trader: Keypair;
+
+  const pda = (seed: string) => marketPda(program, seed, gameId);
+
+  const extendClose = (tradingCloseTs: BN, signer: Keypair = authority.payer) =>
+    program.methods
+      .extendTradingClose(tradingCloseTs)
+      .accounts({ authority: signer.publicKey, market: pda("market") } as any)
+      .signers([signer])
+      .rpc({ commitment: "confirmed" });
+
+  console.log("\n" + "=".repeat(80));
+  console.log("⏰ SportsXchange Trading Close Tests");
+  console.log("=".repeat(80));
+
+  before(async () => {
+    usdcMint = await getUsdcMint(program, provider);
+
+    trader = Keypair.generate();
+    await fundTrader(program, provider, [], usdcMint, trader, 100 * 1e6);
+  });
+
+  it("Rejects a close time in the past", async () => {
+    await expectError(
+      createMarket(program, "2024-WEEK12-PAST", usdcMint, { tradingCloseTs: closeTs(-60) }).rpc(),
+      "InvalidCloseTime"
+    );
+  });
+
+  it("Trades until kickoff", async () => {
+    const tradingCloseTs = closeTs(5);
+    await createMarket(program, gameId, usdcMint, { teamA: "CHI", teamB: "DET", tradingCloseTs }).rpc();
+
+    const market = await program.account.marketV2.fetch(pda("market"));
+    assert.isTrue(market.tradingCloseTs.eq(tradingCloseTs));
+
+    await openTeamAccounts(program, provider, gameId, trader.publicKey);
+
+    await buy(program, gameId, usdcMint, trader, 0, 1e6).rpc();
+  });
+
+  it("Refuses trades after kickoff without a halt", async () => {
+    await sleep(7_000);
+
+    await expectError(buy(program, gameId, usdcMint, trader, 0, 1e6).rpc(), "TradingClosed");
+
+    const market = await program.account.marketV2.fetch(pda("market"));
+    assert.isFalse(market.tradingHalted);
+  });
+
+  it("Only lets the authority push the close time back", async () => {
+    const intruder = Keypair.generate();
+    const sig = await provider.connection.requestAirdrop(intruder.publicKey, LAMPORTS_PER_SOL);
+    await provider.connection.confirmTransaction(sig);
+
+    for (const [attempt, error] of [
+      [() => extendClose(closeTs(3_600), intruder), "ConstraintHasOne"],
+      [() => extendClose(closeTs(-3_600)), "InvalidCloseTime"],
+    ] as const) {
+      await expectError(attempt(), error);
+    }
+  });
+
+  it("Reopens trading when a delayed game's close time is extended", async () => {
+    const before = await program.account.marketV2.fetch(pda("market"));
+    const tradingCloseTs = closeTs(3_600);
+    const sig = await extendClose(tradingCloseTs);
+
+    const [event] = await eventsOf(program, provider, sig);
+    assert.strictEqual(event.name, "tradingCloseExtendedEvent");
+    assert.isTrue(event.data.previousCloseTs.eq(before.tradingCloseTs));
+    assert.isTrue(event.data.tradingCloseTs.eq(tradingCloseTs));
+
+    await buy(program, gameId, usdcMint, trader, 0, 1e6).rpc();
+  });
+});
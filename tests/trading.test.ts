@@ -274,7 +274,8 @@ describe("sportsxchange - Trading", () => {
     const tx = await program.methods
       .swapHomeForAway(
         new anchor.BN(amountIn),
-        new anchor.BN(minimumOut)
+        new anchor.BN(minimumOut),
+        null
       )
       .accounts({
         market: marketPda,
@@ -372,7 +373,8 @@ describe("sportsxchange - Trading", () => {
     const tx = await program.methods
       .swapAwayForHome(
         new anchor.BN(amountIn),
-        new anchor.BN(minimumOut)
+        new anchor.BN(minimumOut),
+        null
       )
       .accounts({
         market: marketPda,
@@ -446,7 +448,8 @@ describe("sportsxchange - Trading", () => {
       await program.methods
         .swapHomeForAway(
           new anchor.BN(swapAmount),
-          new anchor.BN(Math.floor(expectedOut * 0.95)) // 5% slippage tolerance
+          new anchor.BN(Math.floor(expectedOut * 0.95)), // 5% slippage tolerance
+          null
         )
         .accounts({
           market: marketPda,
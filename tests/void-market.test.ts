@@ -0,0 +1,145 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Sportsxchange } from "../target/types/sportsxchange";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { assert } from "chai";
+import {
+  buy,
+  claimRefund,
+  createMarket,
+  eventsOf,
+  expectError,
+  fundTrader,
+  getUsdcMint,
+  marketPda,
+  sell,
+  tokenBalance,
+  traderAccounts,
+} from "./helpers";
+
+describe("sportsxchange - Voided Markets", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Sportsxchange as Program<Sportsxchange>;
+  const authority = provider.wallet as anchor.Wallet;
+
+  const gameId = "2024-WEEK7-KC-DEN";
+
+  let usdcMint: PublicKey;
+  let alice: Keypair;
+  let bob: Keypair;
+  let carol: Keypair;
+  let outsider: Keypair;
+
+  const pda = (seed: string) => marketPda(program, seed, gameId);
+
+  console.log("\n" + "=".repeat(80));
+  console.log("🚫 SportsXchange Voided Market Tests");
+  console.log("=".repeat(80));
+
+  before(async () => {
+    usdcMint = await getUsdcMint(program, provider);
+
+    await createMarket(program, gameId, usdcMint, { teamA: "KC", teamB: "DEN" }).rpc();
+
+    alice = Keypair.generate();
+    bob = Keypair.generate();
+    carol = Keypair.generate();
+    outsider = Keypair.generate();
+    for (const trader of [alice, bob, carol, outsider]) {
+      await fundTrader(program, provider, [gameId], usdcMint, trader);
+    }
+
+    // Both sides traded, including a partial sell and a two-sided holder
+    await buy(program, gameId, usdcMint, alice, 0, 50 * 1e6).rpc();
+    await buy(program, gameId, usdcMint, bob, 1, 20 * 1e6).rpc();
+    await buy(program, gameId, usdcMint, carol, 0, 5 * 1e6).rpc();
+    await buy(program, gameId, usdcMint, carol, 1, 30 * 1e6).rpc();
+    await sell(program, gameId, usdcMint, alice, 0, 10 * 1e6).rpc();
+  });
+
+  it("Rejects a void from a key that is neither authority nor resolver", async () => {
+    await expectError(
+      program.methods
+        .voidMarket()
+        .accounts({ authority: outsider.publicKey, market: pda("market") } as any)
+        .signers([outsider])
+        .rpc(),
+      "Unauthorized"
+    );
+  });
+
+  it("Rejects refunds before the market is voided", async () => {
+    await expectError(claimRefund(program, gameId, usdcMint, alice).rpc(), "MarketNotVoided");
+  });
+
+  it("Voids the market and stops trading", async () => {
+    await program.methods
+      .voidMarket()
+      .accounts({ authority: authority.publicKey, market: pda("market") } as any)
+      .rpc();
+
+    const market = await program.account.marketV2.fetch(pda("market"));
+    assert.isTrue(market.isVoided);
+    assert.isTrue(market.tradingHalted);
+    assert.strictEqual(
+      market.teamAPool.toNumber() + market.teamBPool.toNumber(),
+      market.poolValue.toNumber()
+    );
+
+    await expectError(buy(program, gameId, usdcMint, bob, 1, 1e6).rpc(), "TradingHalted");
+  });
+
+  it("Cannot be resolved once voided", async () => {
+    await expectError(
+      program.methods
+        .resolveMarket(0)
+        .accounts({ resolver: authority.publicKey, market: pda("market") } as any)
+        .rpc(),
+      "MarketVoided"
+    );
+  });
+
+  it("Refunds a two-sided holder for both sides at once", async () => {
+    const before = await program.account.marketV2.fetch(pda("market"));
+    const accounts = traderAccounts(program, gameId, usdcMint, carol.publicKey);
+    const heldA = await tokenBalance(provider, accounts.teamAAccount);
+    const heldB = await tokenBalance(provider, accounts.teamBAccount);
+    const usdcBefore = await tokenBalance(provider, accounts.usdc);
+
+    const sig = await claimRefund(program, gameId, usdcMint, carol).rpc({
+      commitment: "confirmed",
+    });
+
+    const expected =
+      Math.floor((heldA * before.teamAPool.toNumber()) / before.teamASupply.toNumber()) +
+      Math.floor((heldB * before.teamBPool.toNumber()) / before.teamBSupply.toNumber());
+    const refunded = (await tokenBalance(provider, accounts.usdc)) - usdcBefore;
+
+    assert.approximately(refunded, expected, 1);
+    assert.strictEqual(await tokenBalance(provider, accounts.teamAAccount), 0);
+    assert.strictEqual(await tokenBalance(provider, accounts.teamBAccount), 0);
+
+    const [event] = await eventsOf(program, provider, sig);
+    assert.strictEqual(event.name, "refundClaimedEvent");
+    assert.strictEqual(event.data.refund.toNumber(), refunded);
+    assert.strictEqual(event.data.teamAAmount.toNumber(), heldA);
+    assert.strictEqual(event.data.teamBAmount.toNumber(), heldB);
+  });
+
+  it("Pays every remaining holder and leaves the vault solvent", async () => {
+    await claimRefund(program, gameId, usdcMint, bob).rpc();
+    await claimRefund(program, gameId, usdcMint, alice).rpc();
+
+    const market = await program.account.marketV2.fetch(pda("market"));
+    assert.strictEqual(market.teamASupply.toNumber(), 0);
+    assert.strictEqual(market.teamBSupply.toNumber(), 0);
+    assert.strictEqual(market.poolValue.toNumber(), 0);
+    assert.strictEqual(await tokenBalance(provider, pda("usdc_vault")), 0);
+  });
+
+  it("Rejects a refund with nothing to burn", async () => {
+    await expectError(claimRefund(program, gameId, usdcMint, alice).rpc(), "NoTokensToRefund");
+  });
+});